@@ -1,4 +1,6 @@
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
+use std::iter::Sum;
+use std::ops::Add;
 use std::vec::Vec;
 
 /// Returns a vector of all permutations of values within the set using a non-recurisve
@@ -16,7 +18,7 @@ pub fn heap_permutations(all_elements: &HashSet<String>) -> Vec<Vec<&str>> {
     while i < size {
         let cc = c.get_mut(i).unwrap();
         if *cc < i {
-            if i % 2 == 0 {
+            if i.is_multiple_of(2) {
                 elements.swap(0, i);
             } else {
                 elements.swap(*cc, i);
@@ -32,3 +34,109 @@ pub fn heap_permutations(all_elements: &HashSet<String>) -> Vec<Vec<&str>> {
 
     ret
 }
+
+/// Reads a table by columns instead of rows, `k` rows at a time. For each block of `k` rows this
+/// yields one group per column, left to right, holding that column's values from top to bottom.
+/// Only one block is held at once, so the rows can be streamed. A last block of fewer than `k` rows
+/// gives shorter groups, as do rows that are missing columns.
+pub fn column_groups<I, R, T>(rows: I, k: usize) -> ColumnGroups<I::IntoIter, T>
+where
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = T>,
+{
+    assert!(k > 0, "Groups must have at least one row");
+    ColumnGroups {
+        rows: rows.into_iter(),
+        k,
+        pending: VecDeque::new(),
+    }
+}
+
+/// Iterator returned by `column_groups`
+pub struct ColumnGroups<I, T> {
+    rows: I,
+    k: usize,
+    pending: VecDeque<Vec<T>>,
+}
+
+impl<I, R, T> Iterator for ColumnGroups<I, T>
+where
+    I: Iterator<Item = R>,
+    R: IntoIterator<Item = T>,
+{
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.pending.is_empty() {
+            for row in self.rows.by_ref().take(self.k) {
+                for (column, value) in row.into_iter().enumerate() {
+                    if column == self.pending.len() {
+                        self.pending.push_back(Vec::with_capacity(self.k));
+                    }
+                    self.pending[column].push(value);
+                }
+            }
+        }
+        self.pending.pop_front()
+    }
+}
+
+/// Returns true if the sides can make a polygon, which is when every side is shorter than all of
+/// the others put together. For three sides this is the triangle inequality.
+pub fn valid_polygon<T>(sides: &[T]) -> bool
+where
+    T: Copy + Ord + Sum<T> + Add<Output = T>,
+{
+    let Some(longest) = sides.iter().max().copied() else {
+        return false;
+    };
+    let total: T = sides.iter().copied().sum();
+    sides.len() >= 3 && total > longest + longest
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithm::{column_groups, valid_polygon};
+
+    #[test]
+    fn test_column_groups() {
+        let rows = vec![
+            vec![101, 301, 501],
+            vec![102, 302, 502],
+            vec![103, 303, 503],
+            vec![201, 401, 601],
+            vec![202, 402, 602],
+            vec![203, 403, 603],
+        ];
+        let groups: Vec<Vec<i32>> = column_groups(rows.clone(), 3).collect();
+        assert_eq!(groups[0], vec![101, 102, 103]);
+        assert_eq!(groups[1], vec![301, 302, 303]);
+        assert_eq!(groups[3], vec![201, 202, 203]);
+        assert_eq!(groups.len(), 6);
+
+        // Groups of two, leaving a short group at the end
+        let groups: Vec<Vec<i32>> = column_groups(rows[..3].to_vec(), 2).collect();
+        assert_eq!(
+            groups,
+            vec![
+                vec![101, 102],
+                vec![301, 302],
+                vec![501, 502],
+                vec![103],
+                vec![303],
+                vec![503]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_valid_polygon() {
+        assert!(!valid_polygon(&[5, 10, 25]));
+        assert!(!valid_polygon(&[5, 10, 15]));
+        assert!(valid_polygon(&[5, 10, 14]));
+        assert!(valid_polygon(&[1, 1, 1, 2]));
+        assert!(!valid_polygon(&[1, 1, 1, 3]));
+        assert!(!valid_polygon(&[3, 4]));
+        assert!(!valid_polygon::<u32>(&[]));
+    }
+}
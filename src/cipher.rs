@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+/// The lowercase ASCII letters, in order
+pub const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+
+/// The uppercase ASCII letters, in order
+pub const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// How often each of the letters a to z appears in English text, as a percentage
+pub const ENGLISH: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406,
+    6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+/// A rotation (Caesar) cipher. Each character in one of its alphabets is moved the key's number of
+/// places along that alphabet, wrapping around at the end. Separators are swapped for their
+/// counterpart in the other text, and any other character is an error.
+///
+/// ```ignore
+/// let cipher = Cipher::new().alphabet(LOWERCASE).separator('-', ' ');
+/// assert_eq!(cipher.encrypt("hello world", 3), Ok(String::from("khoor-zruog")));
+/// ```
+#[derive(Debug, Default)]
+pub struct Cipher {
+    alphabets: Vec<Vec<char>>,
+    /// Alphabet and position within it of every character that gets rotated
+    positions: HashMap<char, (usize, usize)>,
+    /// Pairs of separators, as they appear in the ciphertext and the plaintext
+    separators: Vec<(char, char)>,
+}
+
+impl Cipher {
+    pub fn new() -> Cipher {
+        Cipher::default()
+    }
+
+    /// Adds an alphabet to rotate through. Each alphabet is rotated on its own, so lowercase and
+    /// uppercase letters stay that way. Panics if a character is already in an alphabet.
+    pub fn alphabet(mut self, letters: &str) -> Cipher {
+        let index = self.alphabets.len();
+        for (i, c) in letters.chars().enumerate() {
+            let previous = self.positions.insert(c, (index, i));
+            assert!(previous.is_none(), "{c:?} is in more than one alphabet");
+        }
+        self.alphabets.push(letters.chars().collect());
+        self
+    }
+
+    /// Adds a separator that is written as `encrypted` in the ciphertext and `plain` in the
+    /// plaintext. Use the same character for both to keep it as it is.
+    pub fn separator(mut self, encrypted: char, plain: char) -> Cipher {
+        self.separators.push((encrypted, plain));
+        self
+    }
+
+    /// Rotates the plaintext forward by the key
+    pub fn encrypt(&self, plaintext: &str, key: i64) -> Result<String, String> {
+        self.rotate(plaintext, key, |&(encrypted, plain)| (plain, encrypted))
+    }
+
+    /// Rotates the ciphertext back by the key, undoing `encrypt`
+    pub fn decrypt(&self, ciphertext: &str, key: i64) -> Result<String, String> {
+        self.rotate(ciphertext, -key, |&pair| pair)
+    }
+
+    /// Finds the key that was most likely used to encrypt the ciphertext, by decrypting it with
+    /// every key and comparing how often each letter turns up against `frequencies`, which gives
+    /// the expected share of each position in the alphabets. Returns the key along with the
+    /// plaintext. Short ciphertexts may well not give the right key.
+    ///
+    /// Panics if there is an alphabet that isn't the same length as the frequencies.
+    pub fn crack(&self, ciphertext: &str, frequencies: &[f64]) -> Result<(i64, String), String> {
+        let size = frequencies.len();
+        assert!(
+            self.alphabets.iter().all(|a| a.len() == size),
+            "Frequencies don't match the alphabets"
+        );
+
+        let mut counts = vec![0u32; size];
+        for c in ciphertext.chars() {
+            if let Some((_, i)) = self.positions.get(&c) {
+                counts[*i] += 1;
+            } else if !self.separators.iter().any(|(encrypted, _)| *encrypted == c) {
+                return Err(format!("Unexpected {c:?} in ciphertext"));
+            }
+        }
+
+        // Pearson's chi-squared statistic, which is lower the closer the counts are to expected
+        let total: u32 = counts.iter().sum();
+        let expected_total: f64 = frequencies.iter().sum();
+        let score = |key: usize| -> f64 {
+            (0..size)
+                .map(|i| {
+                    let expected = frequencies[i] / expected_total * f64::from(total);
+                    let observed = f64::from(counts[(i + key) % size]);
+                    (observed - expected).powi(2) / expected
+                })
+                .sum()
+        };
+
+        let key = (0..size)
+            .min_by(|a, b| score(*a).total_cmp(&score(*b)))
+            .unwrap_or(0) as i64;
+        Ok((key, self.decrypt(ciphertext, key)?))
+    }
+
+    /// Rotates every character by the shift. `pair` orders each separator pair as the character
+    /// to look for and the one to replace it with.
+    fn rotate<F>(&self, text: &str, shift: i64, pair: F) -> Result<String, String>
+    where
+        F: Fn(&(char, char)) -> (char, char),
+    {
+        text.chars()
+            .map(|c| {
+                if let Some(&(alphabet, i)) = self.positions.get(&c) {
+                    let letters = &self.alphabets[alphabet];
+                    let len = letters.len() as i64;
+                    let i = (i as i64 + shift).rem_euclid(len);
+                    Ok(letters[i as usize])
+                } else {
+                    self.separators
+                        .iter()
+                        .map(&pair)
+                        .find(|(from, _)| *from == c)
+                        .map(|(_, to)| to)
+                        .ok_or_else(|| format!("Unexpected {c:?} in text"))
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cipher::{Cipher, ENGLISH, LOWERCASE, UPPERCASE};
+
+    #[test]
+    fn test_rotate() {
+        let cipher = Cipher::new().alphabet(LOWERCASE).separator('-', ' ');
+        assert_eq!(
+            cipher.encrypt("hello world", 3),
+            Ok(String::from("khoor-zruog"))
+        );
+        assert_eq!(
+            cipher.decrypt("khoor-zruog", 3),
+            Ok(String::from("hello world"))
+        );
+        // Keys wrap around, either way
+        assert_eq!(cipher.encrypt("xyz", 29), cipher.encrypt("xyz", 3));
+        assert_eq!(cipher.encrypt("abc", -1), Ok(String::from("zab")));
+        assert_eq!(
+            cipher.encrypt("hello-world", 3),
+            Err(String::from("Unexpected '-' in text"))
+        );
+    }
+
+    #[test]
+    fn test_alphabets() {
+        let cipher = Cipher::new()
+            .alphabet(LOWERCASE)
+            .alphabet(UPPERCASE)
+            .alphabet("0123456789")
+            .separator(' ', ' ')
+            .separator('!', '!');
+        assert_eq!(
+            cipher.encrypt("Zebra 19!", 13),
+            Ok(String::from("Mroen 42!"))
+        );
+    }
+
+    #[test]
+    fn test_crack() {
+        let cipher = Cipher::new()
+            .alphabet(LOWERCASE)
+            .alphabet(UPPERCASE)
+            .separator(' ', ' ');
+        let plaintext = "The quick brown fox jumps over the lazy dog while the sun sets";
+        let ciphertext = cipher.encrypt(plaintext, 17).unwrap();
+        assert_eq!(
+            cipher.crack(&ciphertext, &ENGLISH),
+            Ok((17, String::from(plaintext)))
+        );
+    }
+}
@@ -0,0 +1,402 @@
+use crate::line_parser::{self, ParseError};
+use std::cell::RefCell;
+use std::fmt;
+use std::str::FromStr;
+
+/// Reads a value from the start of some input, returning it along with the input that is left.
+/// Small parsers for numbers, words and literals are combined into bigger ones with the methods
+/// here and the functions in this module, for example a present's dimensions:
+///
+/// ```ignore
+/// let dimensions = number::<u32>()
+///     .skip(literal("x"))
+///     .then(number::<u32>())
+///     .skip(literal("x"))
+///     .then(number::<u32>());
+/// assert_eq!(dimensions.parse("2x3x4"), Ok(((2, 3), 4)));
+/// ```
+pub trait Parser<T> {
+    fn parse_at<'a>(&self, input: Input<'a>) -> Result<(T, Input<'a>), ParseError>;
+
+    /// Reads a value from the whole of the text, failing if anything is left over. When it fails,
+    /// an error that was backtracked from is reported instead if it got further.
+    fn parse(&self, text: &str) -> Result<T, ParseError> {
+        let backtracked = RefCell::new(None);
+        let error = match self.parse_at(Input::new(text, &backtracked)) {
+            Ok((value, rest)) if rest.rest().is_empty() => return Ok(value),
+            Ok((_, rest)) => rest.expected("end of input"),
+            Err(e) => e,
+        };
+        match backtracked.into_inner() {
+            Some(backtracked) => Err(error.either(backtracked)),
+            None => Err(error),
+        }
+    }
+
+    /// Reads a value from every line of the text that is not blank, in the same way as
+    /// `LineParser::parse_lines`
+    fn parse_lines(&self, text: &str) -> Result<Vec<T>, ParseError> {
+        line_parser::parse_lines(text, |line| self.parse(line))
+    }
+
+    /// Converts the value once it has been read
+    fn map<U, F>(self, f: F) -> impl Parser<U>
+    where
+        Self: Sized,
+        F: Fn(T) -> U,
+    {
+        from_fn(move |input| {
+            let (value, rest) = self.parse_at(input)?;
+            Ok((f(value), rest))
+        })
+    }
+
+    /// Converts the value once it has been read, failing at the start of it if that is not
+    /// possible
+    fn try_map<U, E, F>(self, f: F) -> impl Parser<U>
+    where
+        Self: Sized,
+        E: fmt::Display,
+        F: Fn(T) -> Result<U, E>,
+    {
+        from_fn(move |input: Input| {
+            let (value, rest) = self.parse_at(input)?;
+            match f(value) {
+                Ok(value) => Ok((value, rest)),
+                Err(e) => Err(ParseError::Invalid {
+                    position: input.position,
+                    value: String::from(&input.text[input.position..rest.position]),
+                    error: e.to_string(),
+                }),
+            }
+        })
+    }
+
+    /// Reads this and then another value, returning both
+    fn then<U, P>(self, next: P) -> impl Parser<(T, U)>
+    where
+        Self: Sized,
+        P: Parser<U>,
+    {
+        from_fn(move |input| {
+            let (a, rest) = self.parse_at(input)?;
+            let (b, rest) = next.parse_at(rest)?;
+            Ok(((a, b), rest))
+        })
+    }
+
+    /// Reads this and then another value, keeping only this one. Handy for separators.
+    fn skip<U, P>(self, next: P) -> impl Parser<T>
+    where
+        Self: Sized,
+        P: Parser<U>,
+    {
+        self.then(next).map(|(a, _)| a)
+    }
+
+    /// Tries another parser from the same place when this one fails
+    fn or<P>(self, other: P) -> impl Parser<T>
+    where
+        Self: Sized,
+        P: Parser<T>,
+    {
+        from_fn(move |input| {
+            self.parse_at(input)
+                .or_else(|a| other.parse_at(input).map_err(|b| a.either(b)))
+        })
+    }
+}
+
+/// Text being parsed, along with how far through it the parser has got
+#[derive(Clone, Copy, Debug)]
+pub struct Input<'a> {
+    text: &'a str,
+    position: usize,
+    /// The furthest error that a parser backtracked from, shared by the whole parse
+    backtracked: &'a RefCell<Option<ParseError>>,
+}
+
+impl<'a> Input<'a> {
+    pub fn new(text: &'a str, backtracked: &'a RefCell<Option<ParseError>>) -> Input<'a> {
+        Input {
+            text,
+            position: 0,
+            backtracked,
+        }
+    }
+
+    /// The text that has not been read yet
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    /// Moves past the next `len` bytes
+    pub fn advance(self, len: usize) -> Input<'a> {
+        Input {
+            text: self.text,
+            position: self.position + len,
+            backtracked: self.backtracked,
+        }
+    }
+
+    /// Keeps an error that was backtracked from, so it can be reported if the parse fails
+    /// without getting any further
+    pub fn backtrack(&self, error: ParseError) {
+        let mut backtracked = self.backtracked.borrow_mut();
+        let furthest = match backtracked.take() {
+            Some(earlier) => earlier.either(error),
+            None => error,
+        };
+        *backtracked = Some(furthest);
+    }
+
+    /// An error for when something else was found here
+    pub fn expected(&self, expected: &str) -> ParseError {
+        let found = self.rest().lines().next().unwrap_or_default();
+        ParseError::Expected {
+            expected: String::from(expected),
+            position: self.position,
+            found: String::from(found),
+        }
+    }
+}
+
+impl ParseError {
+    fn position(&self) -> usize {
+        match self {
+            ParseError::Expected { position, .. } | ParseError::Invalid { position, .. } => {
+                *position
+            }
+            _ => 0,
+        }
+    }
+
+    /// Combines the errors from two alternatives. The one that got further is more useful, and
+    /// when they failed at the same place either of the expected values would have done.
+    fn either(self, other: ParseError) -> ParseError {
+        match (self, other) {
+            (
+                ParseError::Expected {
+                    expected: a,
+                    position,
+                    found,
+                },
+                ParseError::Expected {
+                    expected: b,
+                    position: p,
+                    ..
+                },
+            ) if position == p => ParseError::Expected {
+                expected: format!("{a} or {b}"),
+                position,
+                found,
+            },
+            (a, b) if b.position() > a.position() => b,
+            (a, _) => a,
+        }
+    }
+}
+
+/// Turns a function into a parser. The function is given the input and returns the value read
+/// along with the input that is left.
+pub fn from_fn<T, F>(f: F) -> FromFn<F>
+where
+    F: for<'a> Fn(Input<'a>) -> Result<(T, Input<'a>), ParseError>,
+{
+    FromFn(f)
+}
+
+/// Parser returned by `from_fn`
+pub struct FromFn<F>(F);
+
+impl<T, F> Parser<T> for FromFn<F>
+where
+    F: for<'a> Fn(Input<'a>) -> Result<(T, Input<'a>), ParseError>,
+{
+    fn parse_at<'a>(&self, input: Input<'a>) -> Result<(T, Input<'a>), ParseError> {
+        (self.0)(input)
+    }
+}
+
+/// Reads exactly the given text
+pub fn literal(expected: &'static str) -> impl Parser<()> {
+    from_fn(move |input| {
+        if input.rest().starts_with(expected) {
+            Ok(((), input.advance(expected.len())))
+        } else {
+            Err(input.expected(&format!("{expected:?}")))
+        }
+    })
+}
+
+/// Reads one or more characters matching the predicate
+pub fn take_while1<P>(description: &'static str, predicate: P) -> impl Parser<String>
+where
+    P: Fn(char) -> bool,
+{
+    from_fn(move |input| {
+        let rest = input.rest();
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        if len == 0 {
+            Err(input.expected(description))
+        } else {
+            Ok((String::from(&rest[..len]), input.advance(len)))
+        }
+    })
+}
+
+/// Reads a word made of ASCII letters
+pub fn word() -> impl Parser<String> {
+    take_while1("a word", |c| c.is_ascii_alphabetic())
+}
+
+/// Reads an unsigned decimal number
+pub fn number<T>() -> impl Parser<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    take_while1("a number", |c| c.is_ascii_digit()).try_map(|s| s.parse::<T>())
+}
+
+/// Reads a decimal number that may start with a sign
+pub fn signed<T>() -> impl Parser<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    from_fn(|input: Input| {
+        let sign = input.rest().starts_with(['-', '+']) as usize;
+        let len = input.rest()[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(input.rest().len() - sign);
+        if len == 0 {
+            return Err(input.advance(sign).expected("a number"));
+        }
+        let rest = input.advance(sign + len);
+        let value = &input.rest()[..sign + len];
+        match value.parse::<T>() {
+            Ok(n) => Ok((n, rest)),
+            Err(e) => Err(ParseError::Invalid {
+                position: input.position,
+                value: String::from(value),
+                error: e.to_string(),
+            }),
+        }
+    })
+}
+
+/// Reads one or more items with a separator between each of them. A separator that is not
+/// followed by an item is left unread, so something else can start with it. If nothing does, the
+/// error from reading that item is reported when it got further than the one that follows.
+pub fn separated<T, U, P, S>(item: P, separator: S) -> impl Parser<Vec<T>>
+where
+    P: Parser<T>,
+    S: Parser<U>,
+{
+    from_fn(move |input| {
+        let (first, mut rest) = item.parse_at(input)?;
+        let mut items = vec![first];
+        while let Ok((_, after)) = separator.parse_at(rest) {
+            match item.parse_at(after) {
+                Ok((next, after)) => {
+                    items.push(next);
+                    rest = after;
+                }
+                Err(e) => {
+                    input.backtrack(e);
+                    break;
+                }
+            }
+        }
+        Ok((items, rest))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::combinator::{Parser, literal, number, separated, signed, word};
+    use crate::line_parser::ParseError;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(number::<u32>().parse("123"), Ok(123));
+        assert_eq!(signed::<i32>().parse("-45"), Ok(-45));
+        assert_eq!(signed::<i32>().parse("+6"), Ok(6));
+        assert_eq!(
+            number::<u8>().parse("300"),
+            Err(ParseError::Invalid {
+                position: 0,
+                value: String::from("300"),
+                error: String::from("number too large to fit in target type")
+            })
+        );
+        assert_eq!(
+            signed::<i32>().parse("-x"),
+            Err(ParseError::Expected {
+                expected: String::from("a number"),
+                position: 1,
+                found: String::from("x")
+            })
+        );
+    }
+
+    #[test]
+    fn test_sequence() {
+        let dimensions = number::<u32>()
+            .skip(literal("x"))
+            .then(number::<u32>())
+            .skip(literal("x"))
+            .then(number::<u32>());
+        assert_eq!(dimensions.parse("2x3x4"), Ok(((2, 3), 4)));
+        assert_eq!(
+            dimensions.parse("2x3").unwrap_err().to_string(),
+            "Expected \"x\" at 3, found \"\""
+        );
+        assert_eq!(
+            dimensions.parse("2x3x4x").unwrap_err().to_string(),
+            "Expected end of input at 5, found \"x\""
+        );
+    }
+
+    #[test]
+    fn test_separated() {
+        // The dash before the number is not taken as a separator
+        let room = separated(word(), literal("-"))
+            .skip(literal("-"))
+            .then(number::<u32>());
+        assert_eq!(
+            room.parse("not-a-room-404"),
+            Ok((
+                vec![String::from("not"), String::from("a"), String::from("room")],
+                404
+            ))
+        );
+
+        // When nothing else starts with the last separator, reading the item after it went further
+        let numbers = separated(number::<u32>(), literal(","));
+        assert_eq!(numbers.parse("1,2"), Ok(vec![1, 2]));
+        assert_eq!(
+            numbers.parse("1,2,x").unwrap_err().to_string(),
+            "Expected a number at 4, found \"x\""
+        );
+    }
+
+    #[test]
+    fn test_or() {
+        let direction = literal("L").map(|_| -1).or(literal("R").map(|_| 1));
+        let rotation = direction.then(number::<i32>());
+        assert_eq!(
+            rotation.parse_lines("L5\n\nR10\n"),
+            Ok(vec![(-1, 5), (1, 10)])
+        );
+        assert_eq!(
+            rotation.parse_lines("L5 \r\n R10\r\n"),
+            Ok(vec![(-1, 5), (1, 10)])
+        );
+        assert_eq!(
+            rotation.parse_lines("L5\nU3").unwrap_err().to_string(),
+            "Line 2: Expected \"L\" or \"R\" at 0, found \"U3\""
+        );
+    }
+}
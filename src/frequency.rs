@@ -0,0 +1,142 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How many times each symbol turns up in a sequence, so they can be ranked from most to least
+/// common. For example the checksum of a room name is its five most common letters, with ties
+/// broken alphabetically:
+///
+/// ```ignore
+/// let ranked = Frequencies::new("aaaaa-bbb-z-y-x".chars().filter(|c| *c != '-'));
+/// assert_eq!(ranked.top(3, TieBreak::Ascending), vec![('a', 5), ('b', 3), ('x', 1)]);
+/// ```
+#[derive(Debug)]
+pub struct Frequencies<S> {
+    /// Number of times each symbol was seen, and the position it was first seen at
+    counts: HashMap<S, (usize, usize)>,
+}
+
+/// How symbols that turn up the same number of times are ordered
+#[derive(Clone, Copy, Debug)]
+pub enum TieBreak {
+    /// Smallest first, which is alphabetical for letters
+    Ascending,
+    /// Largest first
+    Descending,
+    /// In the order they first turned up in
+    FirstSeen,
+}
+
+impl<S> Frequencies<S>
+where
+    S: Hash + Eq + Clone,
+{
+    pub fn new<I>(symbols: I) -> Frequencies<S>
+    where
+        I: IntoIterator<Item = S>,
+    {
+        let mut counts = HashMap::new();
+        for (i, symbol) in symbols.into_iter().enumerate() {
+            counts.entry(symbol).or_insert((0, i)).0 += 1;
+        }
+        Frequencies { counts }
+    }
+
+    /// Number of times the symbol turned up
+    pub fn count(&self, symbol: &S) -> usize {
+        self.counts.get(symbol).map_or(0, |(count, _)| *count)
+    }
+
+    /// The `k` most common symbols with their counts, most common first. There are fewer when
+    /// there aren't `k` different symbols.
+    pub fn top(&self, k: usize, tie_break: TieBreak) -> Vec<(S, usize)>
+    where
+        S: Ord,
+    {
+        self.rank(k, |(a, (_, first_a)), (b, (_, first_b))| match tie_break {
+            TieBreak::Ascending => a.cmp(b),
+            TieBreak::Descending => b.cmp(a),
+            TieBreak::FirstSeen => first_a.cmp(first_b),
+        })
+    }
+
+    /// Like `top`, but symbols that turn up the same number of times are ordered by `compare`
+    pub fn top_by<F>(&self, k: usize, compare: F) -> Vec<(S, usize)>
+    where
+        F: Fn(&S, &S) -> Ordering,
+    {
+        self.rank(k, |(a, _), (b, _)| compare(a, b))
+    }
+
+    fn rank<F>(&self, k: usize, tie_break: F) -> Vec<(S, usize)>
+    where
+        F: Fn((&S, &(usize, usize)), (&S, &(usize, usize))) -> Ordering,
+    {
+        let mut ranked: Vec<(&S, &(usize, usize))> = self.counts.iter().collect();
+        ranked.sort_unstable_by(|a, b| b.1.0.cmp(&a.1.0).then_with(|| tie_break(*a, *b)));
+        ranked
+            .into_iter()
+            .take(k)
+            .map(|(symbol, (count, _))| (symbol.clone(), *count))
+            .collect()
+    }
+}
+
+impl Frequencies<char> {
+    /// Counts the characters in the text
+    pub fn chars(text: &str) -> Frequencies<char> {
+        Frequencies::new(text.chars())
+    }
+}
+
+impl<'a> Frequencies<&'a str> {
+    /// Counts the grapheme clusters in the text, so that characters made of several code points,
+    /// like accented letters and flags, count as one symbol
+    pub fn graphemes(text: &'a str) -> Frequencies<&'a str> {
+        Frequencies::new(text.graphemes(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frequency::{Frequencies, TieBreak};
+
+    #[test]
+    fn test_tie_breaks() {
+        let frequencies = Frequencies::chars("zyxbbbaaaaac");
+        assert_eq!(frequencies.count(&'a'), 5);
+        assert_eq!(frequencies.count(&'q'), 0);
+        assert_eq!(
+            frequencies.top(4, TieBreak::Ascending),
+            vec![('a', 5), ('b', 3), ('c', 1), ('x', 1)]
+        );
+        assert_eq!(
+            frequencies.top(4, TieBreak::Descending),
+            vec![('a', 5), ('b', 3), ('z', 1), ('y', 1)]
+        );
+        assert_eq!(
+            frequencies.top(4, TieBreak::FirstSeen),
+            vec![('a', 5), ('b', 3), ('z', 1), ('y', 1)]
+        );
+        // Vowels before consonants, then alphabetically
+        let vowel = |c: &char| !"aeiou".contains(*c);
+        assert_eq!(
+            Frequencies::chars("dcba").top_by(2, |a, b| vowel(a).cmp(&vowel(b)).then(a.cmp(b))),
+            vec![('a', 1), ('b', 1)]
+        );
+        assert_eq!(frequencies.top(10, TieBreak::Ascending).len(), 6);
+    }
+
+    #[test]
+    fn test_graphemes() {
+        // The e with a combining accent is two chars, but one grapheme
+        let text = "cafe\u{301} cafe\u{301} e";
+        assert_eq!(Frequencies::chars(text).count(&'e'), 3);
+        assert_eq!(
+            Frequencies::graphemes(text).top(2, TieBreak::Ascending),
+            vec![(" ", 2), ("a", 2)]
+        );
+        assert_eq!(Frequencies::graphemes(text).count(&"e\u{301}"), 2);
+    }
+}
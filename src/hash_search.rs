@@ -0,0 +1,508 @@
+use md5::{Digest as _, Md5};
+use std::collections::VecDeque;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+/// Raw bytes of an MD5 hash
+pub type Digest = [u8; 16];
+
+/// Number of candidates each thread hashes per batch
+const CHUNK_SIZE: u64 = 16 * 1024;
+
+/// Environment variable naming the directory that cached hits are stored in
+const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+/// Directory in the project that cached hits are stored in when `AOC_CACHE_DIR` isn't set. It is
+/// found from where the crate was built, not from wherever a run happens to be started.
+const DEFAULT_CACHE_DIR: &str = ".cache/hash_search";
+
+/// Counts the temporary files written, so that searches running at the same time, even in the
+/// same process, never write to the same one
+static TEMP_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// Returns an iterator over up to `count` indexes, starting from 0, where the MD5 hash of the
+/// secret followed by the decimal index satisfies the predicate. Hits are yielded in index order
+/// along with their digest.
+pub fn search<P>(secret: &str, predicate: P, count: usize) -> Search<P>
+where
+    P: Fn(&Digest) -> bool + Sync,
+{
+    let threads = thread::available_parallelism().map_or(1, |n| n.get()) as u64;
+    Search {
+        // Every candidate shares the secret, so hash it once and clone the state per candidate
+        key: Md5::new_with_prefix(secret.as_bytes()),
+        predicate,
+        next: 0,
+        threads,
+        pending: VecDeque::new(),
+        remaining: count,
+    }
+}
+
+/// Like `search`, but hits are remembered on disk so repeating the same search is instant. There
+/// is no way to tell two closures apart, so `description` has to say what the predicate matches,
+/// such as "5 zeros". Searches with the same description and secret share a cache file, and the
+/// cache is thrown away when it was built for a different description, secret or starting index.
+///
+/// The file is replaced as a whole every time a new hit is found, so searches sharing it at the
+/// same time can only undo each other's progress, never leave it half written.
+pub fn cached_search<P>(
+    description: &str,
+    secret: &str,
+    predicate: P,
+    count: usize,
+) -> CachedSearch<P>
+where
+    P: Fn(&Digest) -> bool + Sync,
+{
+    let dir = env::var_os(CACHE_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_CACHE_DIR));
+    CachedSearch {
+        dir,
+        description: String::from(description),
+        secret: String::from(secret),
+        start: 0,
+        search: search(secret, predicate, usize::MAX),
+        cached: None,
+        hits: Vec::new(),
+        writable: true,
+        remaining: count,
+    }
+}
+
+/// Returns a predicate matching digests whose hex representation starts with `n` zeros
+pub fn zeros(n: usize) -> impl Fn(&Digest) -> bool + Sync + Copy {
+    move |digest| has_zero_nibbles(digest, n)
+}
+
+/// Iterator returned by `search`. Candidates are hashed in batches split across all available
+/// cores, and hits are buffered until they are consumed.
+pub struct Search<P> {
+    key: Md5,
+    predicate: P,
+    next: u64,
+    threads: u64,
+    pending: VecDeque<(u64, Digest)>,
+    remaining: usize,
+}
+
+impl<P> Search<P>
+where
+    P: Fn(&Digest) -> bool + Sync,
+{
+    /// Starts the search at the given index instead of 0
+    pub fn starting_at(mut self, start: u64) -> Search<P> {
+        self.next = start;
+        self
+    }
+
+    /// Hashes the next batch of candidates, returning any hits in index order
+    fn next_batch(&mut self) -> Vec<(u64, Digest)> {
+        let base = self.next;
+        self.next += CHUNK_SIZE * self.threads;
+
+        let search = &*self;
+        thread::scope(|s| {
+            let handles: Vec<_> = (0..search.threads)
+                .map(|t| {
+                    let start = base + t * CHUNK_SIZE;
+                    s.spawn(move || search.search_chunk(start, start + CHUNK_SIZE))
+                })
+                .collect();
+
+            // Each chunk is in order, and the chunks are joined in order, so the hits are too
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().expect("Hash search thread panicked"))
+                .collect()
+        })
+    }
+
+    fn search_chunk(&self, start: u64, end: u64) -> Vec<(u64, Digest)> {
+        let mut hits = Vec::new();
+        let mut buf = [0u8; 20];
+        for i in start..end {
+            let digest = self.digest(i, &mut buf);
+            if (self.predicate)(&digest) {
+                hits.push((i, digest));
+            }
+        }
+        hits
+    }
+
+    fn digest(&self, i: u64, buf: &mut [u8; 20]) -> Digest {
+        let mut hasher = self.key.clone();
+        hasher.update(write_decimal(i, buf));
+        hasher.finalize().into()
+    }
+}
+
+impl<P> Iterator for Search<P>
+where
+    P: Fn(&Digest) -> bool + Sync,
+{
+    type Item = (u64, Digest);
+
+    fn next(&mut self) -> Option<(u64, Digest)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        while self.pending.is_empty() {
+            let hits = self.next_batch();
+            self.pending.extend(hits);
+        }
+        self.remaining -= 1;
+        self.pending.pop_front()
+    }
+}
+
+/// Iterator returned by `cached_search`. Hits from the cache file are yielded first, and once they
+/// run out the search carries on from where the file says it got to, saving any new hits.
+///
+/// The file starts with the description, then the secret and starting index, then how far the
+/// search got and how many hits it found before that, and then the index of each of the hits.
+pub struct CachedSearch<P> {
+    dir: PathBuf,
+    description: String,
+    secret: String,
+    start: u64,
+    search: Search<P>,
+    cached: Option<VecDeque<(u64, Digest)>>,
+    /// Every hit found so far, from the file or the search, which is what gets saved
+    hits: Vec<u64>,
+    /// Cleared after the first failure to save, rather than reporting every hit
+    writable: bool,
+    remaining: usize,
+}
+
+impl<P> CachedSearch<P>
+where
+    P: Fn(&Digest) -> bool + Sync,
+{
+    /// Starts the search at the given index instead of 0
+    pub fn starting_at(mut self, start: u64) -> CachedSearch<P> {
+        self.start = start;
+        self.search.next = start;
+        self
+    }
+
+    /// Keeps the cache file in the given directory instead of the default one
+    pub fn in_dir(mut self, dir: impl Into<PathBuf>) -> CachedSearch<P> {
+        self.dir = dir.into();
+        self
+    }
+
+    /// The name of the cache file, made from the description and secret with anything that might
+    /// not be allowed in a file name replaced
+    fn file_name(&self) -> String {
+        format!("{}_{}", self.description, self.secret)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.join(self.file_name())
+    }
+
+    /// The first lines of the cache file, which must match for its hits to be used
+    fn header(&self) -> String {
+        format!("{}\n{} {}", self.description, self.secret, self.start)
+    }
+
+    /// Loads the hits in the cache file, and carries on the search from where it got to. Caching
+    /// is best effort, so a file that can't be read is reported and just means searching from
+    /// scratch.
+    fn load(&mut self) -> VecDeque<(u64, Digest)> {
+        let path = self.path();
+        let (hits, end) = self.read(&path).unwrap_or_else(|e| {
+            eprintln!("Ignoring hash search cache {}: {}", path.display(), e);
+            (VecDeque::new(), self.start)
+        });
+        self.hits = hits.iter().map(|(i, _)| *i).collect();
+        self.search.next = end;
+        hits
+    }
+
+    /// Reads the hits in the cache file, along with the index the search got up to. A missing
+    /// file, or one for a different search, has no hits, and anything else that is wrong with it
+    /// is an error.
+    fn read(&self, path: &Path) -> Result<(VecDeque<(u64, Digest)>, u64), String> {
+        let nothing = Ok((VecDeque::new(), self.start));
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return nothing,
+            Err(e) => return Err(e.to_string()),
+        };
+        let header = self.header();
+        let mut lines = match contents.strip_prefix(header.as_str()) {
+            Some(rest) if rest.starts_with('\n') => rest.lines().skip(1),
+            _ => return nothing,
+        };
+
+        let searched = lines.next().unwrap_or_default();
+        let (end, count) = searched
+            .split_once(' ')
+            .and_then(|(end, count)| Some((end.parse::<u64>().ok()?, count.parse::<usize>().ok()?)))
+            .ok_or_else(|| format!("{:?} is not how far the search got", searched))?;
+
+        // Hashing a single index is cheap, so check every hit rather than trusting the file. The
+        // count catches lines that went missing, which would otherwise skip a hit.
+        let mut buf = [0u8; 20];
+        let mut cached = VecDeque::new();
+        let mut next = self.start;
+        for line in lines {
+            let i = line
+                .parse::<u64>()
+                .map_err(|_| format!("{:?} is not an index", line))?;
+            if i < next || i >= end {
+                return Err(format!("{} is out of order", i));
+            }
+            let digest = self.search.digest(i, &mut buf);
+            if !(self.search.predicate)(&digest) {
+                return Err(format!("{} does not match {}", i, self.description));
+            }
+            cached.push_back((i, digest));
+            next = i + 1;
+        }
+        if cached.len() != count {
+            return Err(format!("Expected {} hits, found {}", count, cached.len()));
+        }
+        Ok((cached, end))
+    }
+
+    /// Replaces the cache file with every hit found so far, up to the end index. It is written
+    /// to a temporary file first and then moved into place, so the cache file is always whole.
+    fn save(&mut self, end: u64) {
+        if !self.writable {
+            return;
+        }
+        let mut contents = format!("{}\n{} {}", self.header(), end, self.hits.len());
+        for i in self.hits.iter() {
+            let _ = write!(contents, "\n{}", i);
+        }
+
+        let path = self.path();
+        let temp = self.dir.join(format!(
+            "{}.{}.{}.tmp",
+            self.file_name(),
+            process::id(),
+            TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let written = fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&temp, contents))
+            .and_then(|_| fs::rename(&temp, &path));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp);
+            eprintln!(
+                "Failed to write hash search cache {}: {}",
+                path.display(),
+                e
+            );
+            self.writable = false;
+        }
+    }
+}
+
+impl<P> Iterator for CachedSearch<P>
+where
+    P: Fn(&Digest) -> bool + Sync,
+{
+    type Item = (u64, Digest);
+
+    fn next(&mut self) -> Option<(u64, Digest)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        if self.cached.is_none() {
+            self.cached = Some(self.load());
+        }
+        if let Some(hit) = self.cached.as_mut().and_then(|c| c.pop_front()) {
+            return Some(hit);
+        }
+
+        // Every index before this hit has been searched, and the hits among them yielded
+        let hit = self.search.next()?;
+        self.hits.push(hit.0);
+        self.save(hit.0 + 1);
+        Some(hit)
+    }
+}
+
+/// Returns true if the hex representation of the digest starts with `n` zeros. This works on the
+/// raw bytes, where each byte holds two hex digits, instead of encoding the digest as a string.
+pub fn has_zero_nibbles(digest: &[u8], n: usize) -> bool {
+    let full = n / 2;
+    digest[..full].iter().all(|b| *b == 0) && (n.is_multiple_of(2) || digest[full] >> 4 == 0)
+}
+
+/// Returns the nth hex digit of the digest
+pub fn nibble(digest: &[u8], n: usize) -> u8 {
+    let b = digest[n / 2];
+    if n.is_multiple_of(2) {
+        b >> 4
+    } else {
+        b & 0x0f
+    }
+}
+
+/// Writes the decimal digits of `n` into the end of `buf`, returning the written digits. This
+/// avoids allocating a string for every candidate.
+fn write_decimal(mut n: u64, buf: &mut [u8; 20]) -> &[u8] {
+    let mut i = buf.len();
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    &buf[i..]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hash_search::{
+        cached_search, has_zero_nibbles, nibble, search, write_decimal, zeros,
+    };
+    use std::collections::VecDeque;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_write_decimal() {
+        let mut buf = [0u8; 20];
+        assert_eq!(write_decimal(0, &mut buf), b"0");
+        assert_eq!(write_decimal(609043, &mut buf), b"609043");
+        assert_eq!(write_decimal(u64::MAX, &mut buf), b"18446744073709551615");
+    }
+
+    #[test]
+    fn test_nibbles() {
+        let digest = [0x00, 0x00, 0x08, 0xf8];
+        assert!(has_zero_nibbles(&digest, 5));
+        assert!(!has_zero_nibbles(&digest, 6));
+        assert_eq!(nibble(&digest, 5), 8);
+        assert_eq!(nibble(&digest, 6), 0xf);
+    }
+
+    #[test]
+    fn test_search() {
+        let hits: Vec<u64> = search("abc", zeros(5), 3).map(|(i, _)| i).collect();
+        assert_eq!(hits, vec![3231929, 5017308, 5278568]);
+    }
+
+    #[test]
+    fn test_cached_search() {
+        let dir = env::temp_dir().join("hash_search_test");
+        let _ = fs::remove_dir_all(&dir);
+        let cached = |secret: &str, count: usize| -> Vec<u64> {
+            cached_search("2 zeros", secret, zeros(2), count)
+                .in_dir(&dir)
+                .map(|(i, _)| i)
+                .collect()
+        };
+        let expected: Vec<u64> = search("abc", zeros(2), 5).map(|(i, _)| i).collect();
+
+        // The first search fills the cache, the second reads it back and carries on past it
+        assert_eq!(cached("abc", 3), expected[..3]);
+        assert_eq!(cached("abc", 5), expected);
+
+        // A different secret must not reuse the hits
+        let expected: Vec<u64> = search("abd", zeros(2), 5).map(|(i, _)| i).collect();
+        assert_eq!(cached("abd", 5), expected);
+    }
+
+    #[test]
+    fn test_corrupt_cache() {
+        let dir = env::temp_dir().join("hash_search_corrupt_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let cached = cached_search("2 zeros", "abc", zeros(2), 1).in_dir(&dir);
+        let path = cached.path();
+        let hits: Vec<u64> = search("abc", zeros(2), 3).map(|(i, _)| i).collect();
+        let read = |contents: String| {
+            fs::write(&path, contents).unwrap();
+            cached
+                .read(&path)
+                .map(|(hits, end)| (hits.into_iter().map(|(i, _)| i).collect(), end))
+        };
+
+        // A missing file, or one for another search, is just empty
+        assert_eq!(cached.read(&path), Ok((VecDeque::new(), 0)));
+        assert_eq!(
+            read(String::from("3 zeros\nabc 0\n2 1\n1")),
+            Ok((vec![], 0))
+        );
+
+        let end = hits[2] + 10;
+        let file = |lines: &str, count: usize| format!("2 zeros\nabc 0\n{end} {count}\n{lines}");
+        let (a, b, c) = (hits[0], hits[1], hits[2]);
+        assert_eq!(
+            read(file(&format!("{a}\n{b}\n{c}"), 3)),
+            Ok((hits.clone(), end))
+        );
+
+        assert_eq!(
+            read(file("abc", 1)),
+            Err(String::from("\"abc\" is not an index"))
+        );
+        assert_eq!(
+            read(file("1", 1)),
+            Err(String::from("1 does not match 2 zeros"))
+        );
+        assert_eq!(
+            read(file(&format!("{b}\n{a}"), 2)),
+            Err(format!("{a} is out of order"))
+        );
+        assert_eq!(
+            read(file(&format!("{a}\n{a}"), 2)),
+            Err(format!("{a} is out of order"))
+        );
+        // A hit past where the search got, or one that went missing
+        assert_eq!(
+            read(format!("2 zeros\nabc 0\n{b} 2\n{a}\n{b}")),
+            Err(format!("{b} is out of order"))
+        );
+        assert_eq!(
+            read(file(&format!("{a}\n{c}"), 3)),
+            Err(String::from("Expected 3 hits, found 2"))
+        );
+        assert!(read(String::from("2 zeros\nabc 0\n")).is_err());
+    }
+
+    #[test]
+    fn test_shared_cache() {
+        // Two searches sharing a file, taking turns to find hits, leave it whole
+        let dir = env::temp_dir().join("hash_search_shared_test");
+        let _ = fs::remove_dir_all(&dir);
+        let mut a = cached_search("2 zeros", "abc", zeros(2), 6).in_dir(&dir);
+        let mut b = cached_search("2 zeros", "abc", zeros(2), 4).in_dir(&dir);
+        let mut found = Vec::new();
+        for _ in 0..4 {
+            found.push(a.next().unwrap().0);
+            b.next().unwrap();
+        }
+        found.extend(a.map(|(i, _)| i));
+
+        let expected: Vec<u64> = search("abc", zeros(2), 6).map(|(i, _)| i).collect();
+        assert_eq!(found, expected);
+        let reread = cached_search("2 zeros", "abc", zeros(2), 6).in_dir(&dir);
+        let (hits, _) = reread.read(&reread.path()).unwrap();
+        assert_eq!(
+            hits.into_iter().map(|(i, _)| i).collect::<Vec<u64>>(),
+            expected
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,336 @@
+use std::io::{BufReader, Bytes, Read};
+use std::iter::Peekable;
+
+/// Sums every number in a JSON document as it is read, without building the document in memory.
+/// When `skip_objects_with` is given, any object (and all of its children) with a property that
+/// has that string value is ignored.
+///
+/// Only the running sum of each array and object that is currently open is kept, so memory use
+/// depends on how deeply the document is nested rather than how big it is. The document must
+/// still be valid JSON.
+///
+/// Only integers are handled: a number with a fraction or an exponent is an error, even where
+/// its value is whole, and so is a sum that does not fit in an i64. Day 12 in decimal mode
+/// accepts those, so the two only agree on documents of small integers.
+pub fn sum_numbers<R: Read>(reader: R, skip_objects_with: Option<&str>) -> Result<i64, String> {
+    let mut tokens = Tokenizer {
+        bytes: BufReader::new(reader).bytes().peekable(),
+    };
+    let skip = skip_objects_with.map(str::as_bytes);
+
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut total: i64 = 0;
+    let mut expect = Expect::Value;
+
+    while let Some(b) = tokens.next_byte()? {
+        match b {
+            b' ' | b'\t' | b'\n' | b'\r' => continue,
+            b',' if expect == Expect::CommaOrClose => {
+                let object = stack.last().is_some_and(|frame| frame.object);
+                expect = if object { Expect::Key } else { Expect::Value };
+                continue;
+            }
+            b':' if expect == Expect::Colon => {
+                expect = Expect::Value;
+                continue;
+            }
+            b'"' if matches!(expect, Expect::Key | Expect::KeyOrClose) => {
+                tokens.string(None)?;
+                expect = Expect::Colon;
+                continue;
+            }
+            b'}' | b']' if expect.closes(b) => {
+                let frame = stack
+                    .pop()
+                    .ok_or_else(|| format!("Unexpected '{}'", char::from(b)))?;
+                if frame.object != (b == b'}') {
+                    return Err(format!("Mismatched '{}'", char::from(b)));
+                }
+                let sum = if frame.skip { 0 } else { frame.sum };
+                add(&mut stack, &mut total, sum)?;
+            }
+            _ if !matches!(expect, Expect::Value | Expect::ValueOrClose) => {
+                return Err(format!("Unexpected '{}'", char::from(b)));
+            }
+            b'{' => {
+                stack.push(Frame::new(true));
+                expect = Expect::KeyOrClose;
+                continue;
+            }
+            b'[' => {
+                stack.push(Frame::new(false));
+                expect = Expect::ValueOrClose;
+                continue;
+            }
+            b'"' => {
+                let matches = tokens.string(skip)?;
+                if let Some(frame) = stack.last_mut() {
+                    frame.skip |= frame.object && matches;
+                }
+            }
+            b'-' | b'0'..=b'9' => {
+                let n = tokens.number(b)?;
+                add(&mut stack, &mut total, n)?;
+            }
+            b't' | b'f' | b'n' => tokens.literal(b)?,
+            _ => return Err(format!("Unexpected '{}'", char::from(b))),
+        }
+        // A whole value has been read
+        expect = if stack.is_empty() {
+            Expect::End
+        } else {
+            Expect::CommaOrClose
+        };
+    }
+
+    if expect == Expect::End {
+        Ok(total)
+    } else {
+        Err(String::from("Unexpected end of document"))
+    }
+}
+
+/// What can come next in the document, apart from whitespace
+#[derive(Clone, Copy, PartialEq)]
+enum Expect {
+    Value,
+    /// The first value of an array, or the end of it
+    ValueOrClose,
+    Key,
+    /// The first key of an object, or the end of it
+    KeyOrClose,
+    Colon,
+    /// Another item after a comma, or the end of the array or object
+    CommaOrClose,
+    /// Nothing, as the whole document has been read
+    End,
+}
+
+impl Expect {
+    fn closes(self, b: u8) -> bool {
+        match self {
+            Expect::CommaOrClose => true,
+            Expect::ValueOrClose => b == b']',
+            Expect::KeyOrClose => b == b'}',
+            _ => false,
+        }
+    }
+}
+
+/// An array or object that is still open
+struct Frame {
+    object: bool,
+    sum: i64,
+    skip: bool,
+}
+
+impl Frame {
+    fn new(object: bool) -> Frame {
+        Frame {
+            object,
+            sum: 0,
+            skip: false,
+        }
+    }
+}
+
+fn add(stack: &mut [Frame], total: &mut i64, n: i64) -> Result<(), String> {
+    let sum = stack.last_mut().map_or(total, |frame| &mut frame.sum);
+    *sum = sum
+        .checked_add(n)
+        .ok_or_else(|| String::from("Sum overflowed"))?;
+    Ok(())
+}
+
+struct Tokenizer<R: Read> {
+    bytes: Peekable<Bytes<BufReader<R>>>,
+}
+
+impl<R: Read> Tokenizer<R> {
+    fn next_byte(&mut self) -> Result<Option<u8>, String> {
+        self.bytes.next().transpose().map_err(|e| e.to_string())
+    }
+
+    fn peek_byte(&mut self) -> Option<u8> {
+        self.bytes.peek().and_then(|b| b.as_ref().ok().copied())
+    }
+
+    /// Reads the rest of a string after its opening quote, returning whether it is equal to
+    /// `target`. The string's UTF-8 bytes are compared as they are read, so long strings are never
+    /// held in memory.
+    fn string(&mut self, target: Option<&[u8]>) -> Result<bool, String> {
+        let target = target.unwrap_or(&[]);
+        let mut matches = true;
+        let mut len = 0;
+        let mut buf = [0; 4];
+        loop {
+            let bytes: &[u8] = match self.next_byte()? {
+                None => return Err(String::from("Unterminated string")),
+                Some(b'"') => break,
+                Some(b'\\') => self.escape()?.encode_utf8(&mut buf).as_bytes(),
+                Some(b) => {
+                    buf[0] = b;
+                    &buf[..1]
+                }
+            };
+            matches &= target.get(len..len + bytes.len()) == Some(bytes);
+            len += bytes.len();
+        }
+        Ok(matches && len == target.len() && !target.is_empty())
+    }
+
+    fn escape(&mut self) -> Result<char, String> {
+        let c = match self.next_byte()? {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                let mut code = 0;
+                for _ in 0..4 {
+                    let digit = self
+                        .next_byte()?
+                        .and_then(|b| char::from(b).to_digit(16))
+                        .ok_or_else(|| String::from("Invalid unicode escape"))?;
+                    code = code * 16 + digit;
+                }
+                char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+            }
+            _ => return Err(String::from("Invalid escape")),
+        };
+        Ok(c)
+    }
+
+    /// Reads an integer, given its first byte
+    fn number(&mut self, first: u8) -> Result<i64, String> {
+        let negative = first == b'-';
+        let mut n: i64 = 0;
+        let mut digits = 0;
+        if !negative {
+            n = i64::from(first - b'0');
+            digits = 1;
+        }
+
+        while let Some(b) = self.peek_byte() {
+            match b {
+                b'0'..=b'9' if digits == 1 && n == 0 => {
+                    return Err(String::from("Numbers can't have leading zeros"));
+                }
+                b'0'..=b'9' => {
+                    self.bytes.next();
+                    // Negative numbers are built up negatively, so i64::MIN can be read too
+                    let d = i64::from(b - b'0');
+                    n = n
+                        .checked_mul(10)
+                        .and_then(|n| {
+                            if negative {
+                                n.checked_sub(d)
+                            } else {
+                                n.checked_add(d)
+                            }
+                        })
+                        .ok_or_else(|| String::from("Number too large"))?;
+                    digits += 1;
+                }
+                b'.' | b'e' | b'E' => return Err(String::from("Only integers can be summed")),
+                _ => break,
+            }
+        }
+
+        if digits == 0 {
+            Err(String::from("Invalid number"))
+        } else {
+            Ok(n)
+        }
+    }
+
+    /// Reads the rest of true, false or null, given its first byte
+    fn literal(&mut self, first: u8) -> Result<(), String> {
+        let expected: &[u8] = match first {
+            b't' => b"rue",
+            b'f' => b"alse",
+            _ => b"ull",
+        };
+        for e in expected {
+            if self.next_byte()? != Some(*e) {
+                return Err(String::from("Invalid literal"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_stream::sum_numbers;
+
+    fn sum(json: &str, skip: Option<&str>) -> Result<i64, String> {
+        sum_numbers(json.as_bytes(), skip)
+    }
+
+    #[test]
+    fn test_sum_numbers() {
+        assert_eq!(sum("[1,2,3]", None), Ok(6));
+        assert_eq!(sum(r#"{"a":{"b":4},"c":-1}"#, None), Ok(3));
+        assert_eq!(sum("[]", None), Ok(0));
+        assert_eq!(sum("-42", None), Ok(-42));
+        assert_eq!(sum(r#"[true, false, null, "12"]"#, None), Ok(0));
+        assert_eq!(sum("[-9223372036854775808]", None), Ok(i64::MIN));
+    }
+
+    #[test]
+    fn test_skip_objects_with() {
+        let red = Some("red");
+        assert_eq!(sum("[1,2,3]", red), Ok(6));
+        assert_eq!(sum(r#"[1,{"c":"red","b":2},3]"#, red), Ok(4));
+        assert_eq!(sum(r#"{"d":"red","e":[1,2,3,4],"f":5}"#, red), Ok(0));
+        assert_eq!(sum(r#"[1,"red",5]"#, red), Ok(6));
+        // Only values count, not keys, and escapes are read before comparing
+        assert_eq!(sum(r#"{"red":1,"b":"red ","c":2}"#, red), Ok(3));
+        assert_eq!(sum(r#"{"a": 1, "b": "r\u0065d"}"#, red), Ok(0));
+        // Characters outside ASCII are compared too, whether written out or escaped
+        let rod = Some("rød");
+        assert_eq!(sum(r#"[1,{"a":"rød","b":2}]"#, rod), Ok(1));
+        assert_eq!(sum(r#"[1,{"a":"r\u00f8d","b":2}]"#, rod), Ok(1));
+        assert_eq!(sum(r#"[1,{"a":"røde","b":2}]"#, rod), Ok(3));
+        assert_eq!(sum(r#"[1,{"a":"rød","b":2}]"#, red), Ok(3));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(sum("[1,2", None).is_err());
+        assert!(sum("[1}", None).is_err());
+        assert!(sum("[1.5]", None).is_err());
+        assert!(sum("[9223372036854775808]", None).is_err());
+        assert!(sum(r#"["abc]"#, None).is_err());
+        assert!(sum("[nil]", None).is_err());
+    }
+
+    #[test]
+    fn test_malformed() {
+        for json in [
+            "",
+            "[1 2]",
+            "1 2",
+            "[,1]",
+            "[1,]",
+            "[1,,2]",
+            r#"{"a" 1}"#,
+            r#"{"a":1,}"#,
+            r#"{1:2}"#,
+            r#"{"a"}"#,
+            r#"["a":1]"#,
+            "[01]",
+            "-01",
+            "-",
+            "[true1]",
+        ] {
+            assert!(sum(json, None).is_err(), "{json:?} was accepted");
+        }
+        assert_eq!(sum(r#" { "a" : [ 0 , -0 ] } "#, None), Ok(0));
+    }
+}
@@ -1,5 +1,13 @@
 pub mod algorithm;
+pub mod cipher;
+pub mod combinator;
 pub mod command_line;
+pub mod frequency;
+pub mod hash_search;
+pub mod json_stream;
+pub mod line_parser;
+pub mod look_say;
+pub mod netpbm;
 
 mod year_2015;
 mod year_2016;
@@ -0,0 +1,324 @@
+use regex::{Captures, Regex};
+use std::fmt;
+use std::str::FromStr;
+
+/// Declares a struct, or an enum with struct variants, that is read from a line of input by a
+/// regex. Each field is filled from the named capture group with the same name, parsed with its
+/// type's `FromStr`. The type gets a `parser()` function returning a `LineParser` for it, which is
+/// compiled the first time it is used and then shared by the whole process.
+///
+/// ```ignore
+/// line_parser! {
+///     struct Distance = r"^(?<from>[a-zA-Z]+) to (?<to>[a-zA-Z]+) = (?<distance>[0-9]+)$" {
+///         from: String,
+///         to: String,
+///         distance: u32,
+///     }
+/// }
+/// ```
+///
+/// For an enum each variant has its own regex, and they are tried in the order they are declared.
+/// The regex may be a string literal or the name of a constant.
+macro_rules! line_parser {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident = $regex:tt {
+            $($(#[$field_meta:meta])* $field:ident : $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field: $ty),*
+        }
+
+        impl $name {
+            /// The parser for the regex this is read from
+            fn parser() -> &'static $crate::line_parser::LineParser<$name> {
+                static PARSER: std::sync::LazyLock<$crate::line_parser::LineParser<$name>> =
+                    std::sync::LazyLock::new(|| {
+                        $crate::line_parser::LineParser::new().format(
+                            $regex,
+                            &[$(stringify!($field)),*],
+                            |c| {
+                                Ok($name {
+                                    $($field: $crate::line_parser::field(c, stringify!($field))?),*
+                                })
+                            },
+                        )
+                    });
+                &PARSER
+            }
+        }
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident { $($field:ident : $ty:ty),* $(,)? } = $regex:tt
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant { $($field: $ty),* }),*
+        }
+
+        impl $name {
+            /// The parser for the regexes this is read from
+            fn parser() -> &'static $crate::line_parser::LineParser<$name> {
+                static PARSER: std::sync::LazyLock<$crate::line_parser::LineParser<$name>> =
+                    std::sync::LazyLock::new(|| {
+                        $crate::line_parser::LineParser::new()
+                            $(.format(
+                                $regex,
+                                &[$(stringify!($field)),*],
+                                // Named so that it can go unused by variants without fields
+                                |_captures| {
+                                    Ok($name::$variant {
+                                        $($field: $crate::line_parser::field(
+                                            _captures,
+                                            stringify!($field),
+                                        )?),*
+                                    })
+                                },
+                            ))*
+                    });
+                &PARSER
+            }
+        }
+    };
+}
+
+pub(crate) use line_parser;
+
+/// Reads values from lines of input by trying each of its regexes in turn
+pub struct LineParser<T> {
+    formats: Vec<(Regex, Build<T>)>,
+}
+
+/// Builds a value from the captures of a matching line
+type Build<T> = fn(&Captures) -> Result<T, ParseError>;
+
+/// Why a line could not be read, either by a `LineParser` or by a parser from `combinator`.
+/// Positions are byte offsets from the start of the text being parsed.
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// The line did not match any of the formats
+    NoMatch(String),
+    /// A captured field could not be parsed as its type
+    Field {
+        field: &'static str,
+        value: String,
+        error: String,
+    },
+    /// Something else was found where a value was expected
+    Expected {
+        expected: String,
+        position: usize,
+        found: String,
+    },
+    /// A value was read but could not be converted, such as a number that is too large
+    Invalid {
+        position: usize,
+        value: String,
+        error: String,
+    },
+    /// Another error, along with the line number, counting from 1, that it happened on
+    Line(usize, Box<ParseError>),
+}
+
+impl<T> Default for LineParser<T> {
+    fn default() -> LineParser<T> {
+        LineParser::new()
+    }
+}
+
+impl<T> LineParser<T> {
+    pub fn new() -> LineParser<T> {
+        LineParser {
+            formats: Vec::new(),
+        }
+    }
+
+    /// Adds a format to try after the existing ones. Panics if the regex is invalid or does not
+    /// have a capture group for every field, as that is a mistake in the program, not the input.
+    pub fn format(mut self, regex: &str, fields: &[&str], build: Build<T>) -> LineParser<T> {
+        let regex = Regex::new(regex).unwrap_or_else(|e| panic!("Invalid line format: {e}"));
+        for field in fields {
+            assert!(
+                regex.capture_names().any(|name| name == Some(field)),
+                "Line format {regex} has no capture group for {field}"
+            );
+        }
+        self.formats.push((regex, build));
+        self
+    }
+
+    /// Reads a value from a line, using the first format that matches it
+    pub fn parse(&self, line: &str) -> Result<T, ParseError> {
+        self.formats
+            .iter()
+            .find_map(|(regex, build)| regex.captures(line).map(|c| build(&c)))
+            .unwrap_or_else(|| Err(ParseError::NoMatch(String::from(line))))
+    }
+
+    /// Reads a value from every line that is not blank
+    pub fn parse_lines(&self, input: &str) -> Result<Vec<T>, ParseError> {
+        parse_lines(input, |line| self.parse(line))
+    }
+}
+
+/// Reads a value from every line that is not blank, with any whitespace around it (including the
+/// carriage return of a Windows line ending) removed first. Errors say which line they were on.
+pub fn parse_lines<T, F>(input: &str, parse: F) -> Result<Vec<T>, ParseError>
+where
+    F: Fn(&str) -> Result<T, ParseError>,
+{
+    parse_each_line(input, parse).collect()
+}
+
+/// Like `parse_lines`, but reads each line only as it is needed, so the values can be streamed
+pub fn parse_each_line<T, F>(input: &str, parse: F) -> impl Iterator<Item = Result<T, ParseError>>
+where
+    F: Fn(&str) -> Result<T, ParseError>,
+{
+    input
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(move |(i, line)| parse(line).map_err(|e| ParseError::Line(i + 1, Box::new(e))))
+}
+
+/// Parses the named capture group as a field's type. Used by `line_parser!`.
+pub fn field<T>(captures: &Captures, name: &'static str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let value = captures.name(name).map_or("", |m| m.as_str());
+    value.parse::<T>().map_err(|e| ParseError::Field {
+        field: name,
+        value: String::from(value),
+        error: e.to_string(),
+    })
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::NoMatch(line) => write!(f, "Unrecognised line {line:?}"),
+            ParseError::Field {
+                field,
+                value,
+                error,
+            } => write!(f, "Invalid {field} {value:?}: {error}"),
+            ParseError::Expected {
+                expected,
+                position,
+                found,
+            } => write!(f, "Expected {expected} at {position}, found {found:?}"),
+            ParseError::Invalid {
+                position,
+                value,
+                error,
+            } => write!(f, "Invalid value {value:?} at {position}: {error}"),
+            ParseError::Line(number, error) => write!(f, "Line {number}: {error}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::line_parser::ParseError;
+
+    line_parser! {
+        #[derive(Debug, PartialEq)]
+        struct Move = r"^(?<direction>[a-z]+) (?<steps>-?[0-9]+)$" {
+            direction: String,
+            steps: i32,
+        }
+    }
+
+    line_parser! {
+        #[derive(Debug, PartialEq)]
+        enum Command {
+            Push { value: u8 } = r"^push (?<value>[0-9]+)$",
+            Pop {} = r"^pop$",
+            Jump { label: String } = r"^(?:jmp|jump) (?<label>[a-z]+)$",
+        }
+    }
+
+    #[test]
+    fn test_struct() {
+        let parser = Move::parser();
+        assert_eq!(
+            parser.parse("up -3"),
+            Ok(Move {
+                direction: String::from("up"),
+                steps: -3
+            })
+        );
+        assert_eq!(
+            parser.parse("up three"),
+            Err(ParseError::NoMatch(String::from("up three")))
+        );
+        assert_eq!(
+            parser.parse("up 9999999999"),
+            Err(ParseError::Field {
+                field: "steps",
+                value: String::from("9999999999"),
+                error: String::from("number too large to fit in target type")
+            })
+        );
+    }
+
+    #[test]
+    fn test_enum() {
+        let parser = Command::parser();
+        assert_eq!(
+            parser.parse_lines("push 4\n\npop\njump start\n"),
+            Ok(vec![
+                Command::Push { value: 4 },
+                Command::Pop {},
+                Command::Jump {
+                    label: String::from("start")
+                }
+            ])
+        );
+
+        // Surrounding whitespace and Windows line endings are ignored
+        assert_eq!(
+            parser.parse_lines("  pop \r\njump end\r\n"),
+            Ok(vec![
+                Command::Pop {},
+                Command::Jump {
+                    label: String::from("end")
+                }
+            ])
+        );
+
+        let error = parser.parse_lines("pop\npush 300").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Line 2: Invalid value \"300\": number too large to fit in target type"
+        );
+    }
+
+    #[test]
+    fn test_shared() {
+        // Every call hands out the same compiled parser
+        assert!(std::ptr::eq(Move::parser(), Move::parser()));
+    }
+
+    #[test]
+    #[should_panic(expected = "no capture group for steps")]
+    fn test_missing_group() {
+        crate::line_parser::LineParser::<Move>::new().format(
+            r"^(?<direction>[a-z]+)$",
+            &["direction", "steps"],
+            |_| Err(ParseError::NoMatch(String::new())),
+        );
+    }
+}
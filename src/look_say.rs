@@ -0,0 +1,440 @@
+use num_bigint::BigUint;
+use std::collections::HashSet;
+
+/// One of Conway's 92 common elements. Every look-and-say sequence eventually becomes a compound
+/// of these, and each element decays into the same elements every step, without interacting with
+/// its neighbours: https://en.wikipedia.org/wiki/Look-and-say_sequence#Cosmological_decay
+struct Element {
+    symbol: &'static str,
+    digits: &'static str,
+    decay: &'static [&'static str],
+}
+
+impl Element {
+    const fn new(
+        symbol: &'static str,
+        digits: &'static str,
+        decay: &'static [&'static str],
+    ) -> Element {
+        Element {
+            symbol,
+            digits,
+            decay,
+        }
+    }
+}
+
+/// Conway's periodic table, in order of atomic number
+const ELEMENTS: [Element; 92] = [
+    Element::new("H", "22", &["H"]),
+    Element::new(
+        "He",
+        "13112221133211322112211213322112",
+        &["Hf", "Pa", "H", "Ca", "Li"],
+    ),
+    Element::new("Li", "312211322212221121123222112", &["He"]),
+    Element::new(
+        "Be",
+        "111312211312113221133211322112211213322112",
+        &["Ge", "Ca", "Li"],
+    ),
+    Element::new("B", "1321132122211322212221121123222112", &["Be"]),
+    Element::new("C", "3113112211322112211213322112", &["B"]),
+    Element::new("N", "111312212221121123222112", &["C"]),
+    Element::new("O", "132112211213322112", &["N"]),
+    Element::new("F", "31121123222112", &["O"]),
+    Element::new("Ne", "111213322112", &["F"]),
+    Element::new("Na", "123222112", &["Ne"]),
+    Element::new("Mg", "3113322112", &["Pm", "Na"]),
+    Element::new("Al", "1113222112", &["Mg"]),
+    Element::new("Si", "1322112", &["Al"]),
+    Element::new("P", "311311222112", &["Ho", "Si"]),
+    Element::new("S", "1113122112", &["P"]),
+    Element::new("Cl", "132112", &["S"]),
+    Element::new("Ar", "3112", &["Cl"]),
+    Element::new("K", "1112", &["Ar"]),
+    Element::new("Ca", "12", &["K"]),
+    Element::new("Sc", "3113112221133112", &["Ho", "Pa", "H", "Ca", "Co"]),
+    Element::new("Ti", "11131221131112", &["Sc"]),
+    Element::new("V", "13211312", &["Ti"]),
+    Element::new("Cr", "31132", &["V"]),
+    Element::new("Mn", "111311222112", &["Cr", "Si"]),
+    Element::new("Fe", "13122112", &["Mn"]),
+    Element::new("Co", "32112", &["Fe"]),
+    Element::new("Ni", "11133112", &["Zn", "Co"]),
+    Element::new("Cu", "131112", &["Ni"]),
+    Element::new("Zn", "312", &["Cu"]),
+    Element::new(
+        "Ga",
+        "13221133122211332",
+        &["Eu", "Ca", "Ac", "H", "Ca", "Zn"],
+    ),
+    Element::new("Ge", "31131122211311122113222", &["Ho", "Ga"]),
+    Element::new("As", "11131221131211322113322112", &["Ge", "Na"]),
+    Element::new("Se", "13211321222113222112", &["As"]),
+    Element::new("Br", "3113112211322112", &["Se"]),
+    Element::new("Kr", "11131221222112", &["Br"]),
+    Element::new("Rb", "1321122112", &["Kr"]),
+    Element::new("Sr", "3112112", &["Rb"]),
+    Element::new("Y", "1112133", &["Sr", "U"]),
+    Element::new("Zr", "12322211331222113112211", &["Y", "H", "Ca", "Tc"]),
+    Element::new("Nb", "1113122113322113111221131221", &["Er", "Zr"]),
+    Element::new("Mo", "13211322211312113211", &["Nb"]),
+    Element::new("Tc", "311322113212221", &["Mo"]),
+    Element::new("Ru", "132211331222113112211", &["Eu", "Ca", "Tc"]),
+    Element::new("Rh", "311311222113111221131221", &["Ho", "Ru"]),
+    Element::new("Pd", "111312211312113211", &["Rh"]),
+    Element::new("Ag", "132113212221", &["Pd"]),
+    Element::new("Cd", "3113112211", &["Ag"]),
+    Element::new("In", "11131221", &["Cd"]),
+    Element::new("Sn", "13211", &["In"]),
+    Element::new("Sb", "3112221", &["Pm", "Sn"]),
+    Element::new("Te", "1322113312211", &["Eu", "Ca", "Sb"]),
+    Element::new("I", "311311222113111221", &["Ho", "Te"]),
+    Element::new("Xe", "11131221131211", &["I"]),
+    Element::new("Cs", "13211321", &["Xe"]),
+    Element::new("Ba", "311311", &["Cs"]),
+    Element::new("La", "11131", &["Ba"]),
+    Element::new("Ce", "1321133112", &["La", "H", "Ca", "Co"]),
+    Element::new("Pr", "31131112", &["Ce"]),
+    Element::new("Nd", "111312", &["Pr"]),
+    Element::new("Pm", "132", &["Nd"]),
+    Element::new("Sm", "311332", &["Pm", "Ca", "Zn"]),
+    Element::new("Eu", "1113222", &["Sm"]),
+    Element::new("Gd", "13221133112", &["Eu", "Ca", "Co"]),
+    Element::new("Tb", "3113112221131112", &["Ho", "Gd"]),
+    Element::new("Dy", "111312211312", &["Tb"]),
+    Element::new("Ho", "1321132", &["Dy"]),
+    Element::new("Er", "311311222", &["Ho", "Pm"]),
+    Element::new("Tm", "11131221133112", &["Er", "Ca", "Co"]),
+    Element::new("Yb", "1321131112", &["Tm"]),
+    Element::new("Lu", "311312", &["Yb"]),
+    Element::new("Hf", "11132", &["Lu"]),
+    Element::new(
+        "Ta",
+        "13112221133211322112211213322113",
+        &["Hf", "Pa", "H", "Ca", "W"],
+    ),
+    Element::new("W", "312211322212221121123222113", &["Ta"]),
+    Element::new(
+        "Re",
+        "111312211312113221133211322112211213322113",
+        &["Ge", "Ca", "W"],
+    ),
+    Element::new("Os", "1321132122211322212221121123222113", &["Re"]),
+    Element::new("Ir", "3113112211322112211213322113", &["Os"]),
+    Element::new("Pt", "111312212221121123222113", &["Ir"]),
+    Element::new("Au", "132112211213322113", &["Pt"]),
+    Element::new("Hg", "31121123222113", &["Au"]),
+    Element::new("Tl", "111213322113", &["Hg"]),
+    Element::new("Pb", "123222113", &["Tl"]),
+    Element::new("Bi", "3113322113", &["Pm", "Pb"]),
+    Element::new("Po", "1113222113", &["Bi"]),
+    Element::new("At", "1322113", &["Po"]),
+    Element::new("Rn", "311311222113", &["Ho", "At"]),
+    Element::new("Fr", "1113122113", &["Rn"]),
+    Element::new("Ra", "132113", &["Fr"]),
+    Element::new("Ac", "3113", &["Ra"]),
+    Element::new("Th", "1113", &["Ac"]),
+    Element::new("Pa", "13", &["Th"]),
+    Element::new("U", "3", &["Pa"]),
+];
+
+/// Most steps a seed is read aloud for while waiting for it to split into elements. Once every
+/// run is at most three long, which is true of anything that has been read aloud, Conway showed
+/// that it takes at most 24 steps.
+const SPLIT_STEP_LIMIT: usize = 24;
+
+/// Returns the length of the look-and-say sequence after `iterations` steps from the seed, without
+/// building the sequence. The seed is read aloud until it splits into Conway's elements, and from
+/// then on only the number of each element is tracked as they decay.
+///
+/// Returns None if the seed has anything other than digits, or if reading it aloud once gives any
+/// digit other than 1, 2 or 3, as then it never splits into the common elements. That is when
+/// the seed has such a digit itself or a run of four or more of the same digit, unless the length
+/// is wanted before it would have split.
+pub fn length_after(seed: &str, iterations: usize) -> Option<BigUint> {
+    let decays = decay_table();
+
+    let mut simulation = Simulation::new(seed)?;
+    let mut step = 0;
+    let elements = loop {
+        if let Some(elements) = decompose(simulation.digits(), &decays) {
+            break elements;
+        }
+        if step == iterations {
+            return Some(BigUint::from(simulation.len()));
+        }
+        if step == SPLIT_STEP_LIMIT
+            || (step > 0
+                && simulation
+                    .digits()
+                    .iter()
+                    .any(|d| !(b'1'..=b'3').contains(d)))
+        {
+            return None;
+        }
+        simulation.step();
+        step += 1;
+    };
+
+    let mut counts = vec![BigUint::ZERO; ELEMENTS.len()];
+    for e in elements {
+        counts[e] += 1u32;
+    }
+
+    for _ in step..iterations {
+        let mut next = vec![BigUint::ZERO; ELEMENTS.len()];
+        for (e, count) in counts.iter().enumerate() {
+            for d in decays[e].iter() {
+                next[*d] += count;
+            }
+        }
+        counts = next;
+    }
+
+    let length = counts
+        .iter()
+        .zip(ELEMENTS.iter())
+        .map(|(count, element)| count * element.digits.len())
+        .sum();
+    Some(length)
+}
+
+/// Splits a sequence of digits into the elements it is made of, returning their indexes into
+/// `ELEMENTS`, or None if it is not yet a compound of them.
+///
+/// Not every string of elements is a compound: 33 is not two uranium atoms, as it is read as 23
+/// rather than 1313. So the split with the fewest elements is taken, and it is only accepted if
+/// `stays_split` shows the elements never interact.
+fn decompose(digits: &[u8], decays: &[Vec<usize>]) -> Option<Vec<usize>> {
+    // splits[i] holds the element ending the shortest split of the first i digits, along with the
+    // number of elements in that split
+    let mut splits: Vec<Option<(usize, usize)>> = vec![None; digits.len() + 1];
+    splits[0] = Some((0, 0));
+    for end in 1..=digits.len() {
+        splits[end] = ELEMENTS
+            .iter()
+            .enumerate()
+            .filter(|(_, element)| digits[..end].ends_with(element.digits.as_bytes()))
+            .filter_map(|(e, element)| {
+                splits[end - element.digits.len()].map(|(_, count)| (e, count + 1))
+            })
+            .min_by_key(|(_, count)| *count);
+    }
+
+    let mut elements = Vec::new();
+    let mut end = digits.len();
+    while end > 0 {
+        let (e, _) = splits[end]?;
+        elements.push(e);
+        end -= ELEMENTS[e].digits.len();
+    }
+    elements.reverse();
+
+    stays_split(&elements, decays).then_some(elements)
+}
+
+/// Whether the elements decay separately forever. Reading two strings aloud one after the other
+/// gives the same as reading them aloud together exactly when the last digit of the first differs
+/// from the first digit of the second. So every pair of neighbouring elements that can ever turn
+/// up is checked: those in the compound, those inside the decay of each element that turns up, and
+/// the last and first elements of the decays of a pair that has already turned up. They are all
+/// pairs of the 92 elements, so there are only so many to check.
+fn stays_split(elements: &[usize], decays: &[Vec<usize>]) -> bool {
+    let mut pairs: Vec<(usize, usize)> = elements.windows(2).map(|w| (w[0], w[1])).collect();
+    let mut reached = HashSet::new();
+    let mut unexplored = elements.to_vec();
+    while let Some(e) = unexplored.pop() {
+        if reached.insert(e) {
+            pairs.extend(decays[e].windows(2).map(|w| (w[0], w[1])));
+            unexplored.extend(decays[e].iter().copied());
+        }
+    }
+
+    let mut checked = HashSet::new();
+    while let Some((a, b)) = pairs.pop() {
+        if !checked.insert((a, b)) {
+            continue;
+        }
+        if ELEMENTS[a].digits.as_bytes().last() == ELEMENTS[b].digits.as_bytes().first() {
+            return false;
+        }
+        pairs.push((decays[a][decays[a].len() - 1], decays[b][0]));
+    }
+    true
+}
+
+/// Resolves the decay products of each element to indexes into `ELEMENTS`
+fn decay_table() -> Vec<Vec<usize>> {
+    ELEMENTS
+        .iter()
+        .map(|element| element.decay.iter().map(|s| index_of(s)).collect())
+        .collect()
+}
+
+fn index_of(symbol: &str) -> usize {
+    ELEMENTS
+        .iter()
+        .position(|element| element.symbol == symbol)
+        .unwrap_or_else(|| panic!("Unknown element: {symbol}"))
+}
+
+/// Returns the length of the sequence after each step, starting with the seed itself. Works for
+/// any seed of digits, or returns None if it has anything else.
+#[cfg(test)]
+fn length_series(seed: &str, iterations: usize) -> Option<Vec<usize>> {
+    let mut simulation = Simulation::new(seed)?;
+    let mut series = vec![simulation.len()];
+    for _ in 0..iterations {
+        simulation.step();
+        series.push(simulation.len());
+    }
+    Some(series)
+}
+
+/// Builds the look-and-say sequence step by step as ASCII digits. The two buffers are swapped
+/// each step rather than allocating a new sequence.
+pub struct Simulation {
+    current: Vec<u8>,
+    next: Vec<u8>,
+}
+
+impl Simulation {
+    /// Starts a simulation from any seed of digits, or returns None if it has anything else
+    pub fn new(seed: &str) -> Option<Simulation> {
+        if !seed.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Some(Simulation {
+            current: seed.as_bytes().to_vec(),
+            next: Vec::new(),
+        })
+    }
+
+    pub fn step(&mut self) {
+        self.next.clear();
+
+        for run in self.current.chunk_by(|a, b| a == b) {
+            // Runs are nearly always shorter than ten, but a seed can start with a longer one
+            let count = run.len();
+            if count < 10 {
+                self.next.push(b'0' + count as u8);
+            } else {
+                self.next.extend_from_slice(count.to_string().as_bytes());
+            }
+            self.next.push(run[0]);
+        }
+
+        std::mem::swap(&mut self.current, &mut self.next);
+    }
+
+    pub fn digits(&self) -> &[u8] {
+        &self.current
+    }
+
+    fn len(&self) -> usize {
+        self.current.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::look_say::{
+        ELEMENTS, SPLIT_STEP_LIMIT, Simulation, decay_table, decompose, index_of, length_after,
+        length_series,
+    };
+
+    #[test]
+    fn test_elements_decay() {
+        // Reading each element aloud must give exactly the elements it decays into
+        for element in ELEMENTS.iter() {
+            let decayed: String = element
+                .decay
+                .iter()
+                .map(|symbol| ELEMENTS[index_of(symbol)].digits)
+                .collect();
+            let mut simulation = Simulation::new(element.digits).unwrap();
+            simulation.step();
+            assert_eq!(
+                simulation.digits(),
+                decayed.as_bytes(),
+                "{}",
+                element.symbol
+            );
+        }
+    }
+
+    #[test]
+    fn test_decompose() {
+        let decays = decay_table();
+        assert_eq!(
+            decompose(b"1321131112", &decays),
+            Some(vec![index_of("Yb")])
+        );
+        assert_eq!(
+            decompose(b"22312", &decays),
+            Some(vec![index_of("H"), index_of("Zn")])
+        );
+        assert_eq!(decompose(b"33", &decays), None);
+        assert_eq!(decompose(b"4", &decays), None);
+        assert_eq!(decompose(b"2222", &decays), None);
+    }
+
+    #[test]
+    fn test_any_seed() {
+        // Seeds that aren't already elements are read aloud until they are, and must agree with
+        // building the whole sequence
+        for seed in ["1", "2", "3", "11", "333", "1113122", "2321", "312211223"] {
+            let mut simulation = Simulation::new(seed).unwrap();
+            for iterations in 0..=SPLIT_STEP_LIMIT + 6 {
+                assert_eq!(
+                    length_after(seed, iterations),
+                    Some(simulation.len().into()),
+                    "{seed} after {iterations}"
+                );
+                simulation.step();
+            }
+        }
+
+        // Digits other than 1, 2 and 3 never go away
+        for seed in ["4", "1111", "10", "é", "1é"] {
+            assert_eq!(length_after(seed, 30), None, "{seed}");
+        }
+        assert_eq!(length_after("4", 0), Some(1u32.into()));
+        assert_eq!(length_after("", 10), Some(0u32.into()));
+    }
+
+    #[test]
+    fn test_length_after() {
+        assert_eq!(length_after("3", 0), Some(1u32.into()));
+        assert_eq!(length_after("3", 5), Some(10u32.into()));
+        assert_eq!(length_after("1321131112", 40), Some(492982u32.into()));
+
+        // Far too long to build, but the length is easy to work out
+        let length = length_after("1321131112", 1000).unwrap().to_string();
+        assert_eq!(length.len(), 117);
+        assert!(length.starts_with("167654956196"));
+    }
+
+    #[test]
+    fn test_simulation() {
+        let mut simulation = Simulation::new("1").unwrap();
+        for expected in ["11", "21", "1211", "111221", "312211"] {
+            simulation.step();
+            assert_eq!(simulation.digits(), expected.as_bytes());
+        }
+
+        // Runs of ten or more are read out in full
+        let mut simulation = Simulation::new("0000000000007").unwrap();
+        simulation.step();
+        assert_eq!(simulation.digits(), b"12017");
+
+        assert!(Simulation::new("12a").is_none());
+    }
+
+    #[test]
+    fn test_length_series() {
+        assert_eq!(length_series("1", 5), Some(vec![1, 2, 2, 4, 6, 6]));
+    }
+}
@@ -0,0 +1,88 @@
+use std::io::{self, Write};
+
+/// Writes a binary (P4) portable bitmap. Pixels are given row by row starting at the top left,
+/// with `true` meaning a lit pixel. Lit pixels are written white, so the image looks like the
+/// lights it was generated from: http://netpbm.sourceforge.net/doc/pbm.html
+pub fn write_pbm<W: Write>(
+    out: &mut W,
+    width: usize,
+    height: usize,
+    pixels: &[bool],
+) -> io::Result<()> {
+    assert_eq!(
+        pixels.len(),
+        width * height,
+        "Pixel count does not match dimensions"
+    );
+
+    write!(out, "P4\n{} {}\n", width, height)?;
+
+    // Each row is padded out to a whole number of bytes, most significant bit first. In PBM a set
+    // bit is black, so lit pixels are written as clear bits.
+    let row_bytes = width.div_ceil(8);
+    let mut row = vec![0u8; row_bytes];
+    for line in pixels.chunks(width) {
+        row.iter_mut().for_each(|b| *b = 0);
+        for (x, lit) in line.iter().enumerate() {
+            if !lit {
+                row[x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        out.write_all(&row)?;
+    }
+    Ok(())
+}
+
+/// Writes a binary (P5) portable graymap with the given maximum gray value. Values above 255 are
+/// written as two big endian bytes, as required by the format:
+/// http://netpbm.sourceforge.net/doc/pgm.html
+pub fn write_pgm<W: Write>(
+    out: &mut W,
+    width: usize,
+    height: usize,
+    max_value: u16,
+    pixels: &[u16],
+) -> io::Result<()> {
+    assert_eq!(
+        pixels.len(),
+        width * height,
+        "Pixel count does not match dimensions"
+    );
+
+    // A maximum value of zero is not allowed, so an all black image still claims a range of 1
+    let max_value = u16::max(max_value, 1);
+    write!(out, "P5\n{} {}\n{}\n", width, height, max_value)?;
+
+    let bytes: Vec<u8> = if max_value > 255 {
+        pixels.iter().flat_map(|p| p.to_be_bytes()).collect()
+    } else {
+        pixels.iter().map(|p| *p as u8).collect()
+    };
+    out.write_all(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::netpbm::{write_pbm, write_pgm};
+
+    #[test]
+    fn test_pbm() {
+        let mut out: Vec<u8> = Vec::new();
+        let pixels = [
+            true, false, true, false, false, false, false, false, false, true,
+        ];
+        write_pbm(&mut out, 10, 1, &pixels).unwrap();
+        assert_eq!(out, b"P4\n10 1\n\x5f\x80");
+    }
+
+    #[test]
+    fn test_pgm() {
+        let mut out: Vec<u8> = Vec::new();
+        write_pgm(&mut out, 2, 1, 7, &[0, 7]).unwrap();
+        assert_eq!(out, b"P5\n2 1\n7\n\x00\x07");
+
+        let mut out: Vec<u8> = Vec::new();
+        write_pgm(&mut out, 1, 1, 300, &[258]).unwrap();
+        assert_eq!(out, b"P5\n1 1\n300\n\x01\x02");
+    }
+}
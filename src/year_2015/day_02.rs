@@ -39,6 +39,7 @@
 /// How many total feet of ribbon should they order?
 ///
 /// Your puzzle answer was 3812909.
+use crate::combinator::{Parser, literal, number};
 use indoc::indoc;
 use std::cmp;
 use std::time::SystemTime;
@@ -101,18 +102,13 @@ impl Present {
     }
 
     fn all(input: &str) -> Vec<Present> {
-        let mut presents: Vec<Present> = vec![];
-        let lines = input.split("\n");
-        for line in lines {
-            let dims: Vec<&str> = line.split("x").collect();
-            let present = Present::new(
-                dims[0].parse().unwrap(),
-                dims[1].parse().unwrap(),
-                dims[2].parse().unwrap(),
-            );
-            presents.push(present);
-        }
-        presents
+        let present = number()
+            .skip(literal("x"))
+            .then(number())
+            .skip(literal("x"))
+            .then(number())
+            .map(|((length, width), height)| Present::new(length, width, height));
+        present.parse_lines(input).expect("Invalid input")
     }
 
     fn surface_area(&self) -> u32 {
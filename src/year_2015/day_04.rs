@@ -20,7 +20,7 @@
 /// Now find one that starts with six zeroes.
 ///
 /// Your puzzle answer was 9962624.
-use md5::{Digest, Md5};
+use crate::hash_search::{cached_search, zeros};
 use std::time::SystemTime;
 
 pub fn run() {
@@ -42,26 +42,20 @@ pub fn run() {
 }
 
 fn part_a() -> u64 {
-    mine("00000")
+    mine(5)
 }
 
 fn part_b() -> u64 {
-    mine("000000")
+    mine(6)
 }
 
-fn mine(prefix: &str) -> u64 {
-    let mut found = false;
-    let mut x: u64 = 0;
-    while !found {
-        x += 1;
-
-        let key = format!("{}{}", INPUT_A, x);
-        let mut hasher = Md5::new();
-        hasher.update(key);
-        let result_as_bytes = hasher.finalize();
-        let result = format!("{:x}", result_as_bytes);
-        found = result.starts_with(prefix);
-    }
+fn mine(n: usize) -> u64 {
+    // Only positive numbers count, so start the search at 1
+    let description = format!("{} zeros", n);
+    let (x, _) = cached_search(&description, INPUT_A, zeros(n), 1)
+        .starting_at(1)
+        .next()
+        .unwrap();
     x
 }
 
@@ -46,48 +46,105 @@
 /// - toggle 0,0 through 999,999 would increase the total brightness by 2000000.
 ///
 /// Your puzzle answer was 15343601.
+use crate::line_parser::line_parser;
+use crate::netpbm::{write_pbm, write_pgm};
 use indoc::indoc;
-use regex::Regex;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
 use std::time::SystemTime;
 
 pub fn run() {
     println!("--- Day 6: Probably a Fire Hazard ---");
 
     let now = SystemTime::now();
-    let answer_a = part_a();
+    let instructions = parse_input(INPUT_A);
+    let duration = now.elapsed().expect("Elapsed failed");
+    println!("Parsed the instructions\n in {}ms", duration.as_millis());
+
+    let now = SystemTime::now();
+    let answer_a = part_a(&instructions);
     let duration = now.elapsed().expect("Elapsed failed");
     println!("After following the instructions, how many lights are lit?");
     println!(" {}", answer_a);
     println!(" in {}ms", duration.as_millis());
 
     let now = SystemTime::now();
-    let answer_b = part_b();
+    let answer_b = part_b(&instructions);
     let duration = now.elapsed().expect("Elapsed failed");
     println!(
         "What is the total brightness of all lights combined after following Santa's instructions?"
     );
     println!(" {}", answer_b);
     println!(" in {}ms", duration.as_millis());
+
+    // Rendering is opt in, as the frames for an animation add up to a few hundred megabytes
+    if let Ok(dir) = env::var(RENDER_DIR_VAR) {
+        let frames = env::var(RENDER_FRAMES_VAR).is_ok();
+        match render(Path::new(&dir), &instructions, frames) {
+            Ok(()) => println!("Rendered light grids to {}", dir),
+            Err(e) => println!("Failed to render light grids to {}: {}", dir, e),
+        }
+    }
 }
 
-fn part_a() -> usize {
-    let mut grid = Grid::new();
+fn part_a(instructions: &[Instruction]) -> u32 {
+    let mut grid: Grid<OnOff> = Grid::new(GRID_SIZE);
 
-    for line in INPUT_A.lines() {
-        let instruction = parse_input_line(line);
-        grid.execute(&instruction);
+    for instruction in instructions {
+        grid.execute(instruction);
     }
-    grid.num_on()
+    grid.total()
 }
 
-fn part_b() -> u32 {
-    let mut grid = Grid2::new();
+fn part_b(instructions: &[Instruction]) -> u32 {
+    let mut grid: Grid<Brightness> = Grid::new(GRID_SIZE);
 
-    for line in INPUT_A.lines() {
-        let instruction = parse_input_line(line);
-        grid.execute(&instruction);
+    for instruction in instructions {
+        grid.execute(instruction);
     }
-    grid.total_brightness()
+    grid.total()
+}
+
+/// Renders the final light grids to `dir`, as a bitmap for part A and a graymap of the brightness
+/// for part B. When `frames` is set, an image is also written after every instruction so the
+/// images can be stitched together into an animation.
+fn render(dir: &Path, instructions: &[Instruction], frames: bool) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    render_grid::<OnOff>(dir, "day_06_a", instructions, frames)?;
+    render_grid::<Brightness>(dir, "day_06_b", instructions, frames)
+}
+
+fn render_grid<S: Semantics>(
+    dir: &Path,
+    name: &str,
+    instructions: &[Instruction],
+    frames: bool,
+) -> io::Result<()> {
+    // Every frame of the animation shares the same scale, so find the highest value any light
+    // gets over the whole run before writing any of them
+    let mut peak = 0;
+    if frames {
+        let mut grid: Grid<S> = Grid::new(GRID_SIZE);
+        for instruction in instructions.iter() {
+            grid.execute(instruction);
+            peak = u32::max(peak, grid.peak());
+        }
+    }
+
+    let mut grid: Grid<S> = Grid::new(GRID_SIZE);
+    for (i, instruction) in instructions.iter().enumerate() {
+        grid.execute(instruction);
+        if frames {
+            let path = dir.join(format!("{}_{:03}.{}", name, i + 1, S::EXTENSION));
+            grid.write_image(&path, peak)?;
+        }
+    }
+    let path = dir.join(format!("{}.{}", name, S::EXTENSION));
+    grid.write_image(&path, grid.peak())
 }
 
 #[derive(Debug)]
@@ -97,157 +154,195 @@ enum Command {
     Toggle,
 }
 
-impl Command {
-    fn from(s: &str) -> Command {
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Command, String> {
         match s {
-            "turn on" => Command::On,
-            "turn off" => Command::Off,
-            "toggle" => Command::Toggle,
-            _ => panic!(),
+            "turn on" => Ok(Command::On),
+            "turn off" => Ok(Command::Off),
+            "toggle" => Ok(Command::Toggle),
+            _ => Err(String::from("Expected turn on, turn off or toggle")),
         }
     }
 }
 
-#[derive(Debug)]
-struct Instruction {
-    command: Command,
-    sx: usize,
-    sy: usize,
-    ex: usize,
-    ey: usize,
+line_parser! {
+    #[derive(Debug)]
+    struct Instruction = INPUT_REGEX {
+        command: Command,
+        sx: usize,
+        sy: usize,
+        ex: usize,
+        ey: usize,
+    }
 }
 
-impl Instruction {
-    fn new(command: Command, sx: usize, sy: usize, ex: usize, ey: usize) -> Instruction {
-        Instruction {
-            command,
-            sx,
-            sy,
-            ex,
-            ey,
-        }
-    }
+/// What each command does to a single light. The grid applies commands through this, so a new set
+/// of rules only needs a new implementation rather than a new grid.
+trait Semantics {
+    type Light: Copy + Default;
+
+    /// File extension of the images written by `write_image`
+    const EXTENSION: &str;
+
+    fn on(light: &mut Self::Light);
+    fn off(light: &mut Self::Light);
+    fn toggle(light: &mut Self::Light);
+
+    /// How much a light contributes to the total of the grid
+    fn value(light: &Self::Light) -> u32;
+
+    /// Writes a square of lights as an image, scaled so that `peak` is the brightest a light can
+    /// be drawn
+    fn write_image<W: Write>(
+        out: &mut W,
+        size: usize,
+        lights: &[Self::Light],
+        peak: u32,
+    ) -> io::Result<()>;
 }
 
-struct Grid {
-    grid: Vec<bool>,
+/// Part A: lights are either on or off
+struct OnOff;
+
+impl Semantics for OnOff {
+    type Light = bool;
+
+    const EXTENSION: &str = "pbm";
+
+    fn on(light: &mut bool) {
+        *light = true;
+    }
+
+    fn off(light: &mut bool) {
+        *light = false;
+    }
+
+    fn toggle(light: &mut bool) {
+        *light = !*light;
+    }
+
+    fn value(light: &bool) -> u32 {
+        u32::from(*light)
+    }
+
+    fn write_image<W: Write>(
+        out: &mut W,
+        size: usize,
+        lights: &[bool],
+        _peak: u32,
+    ) -> io::Result<()> {
+        write_pbm(out, size, size, lights)
+    }
 }
 
-impl Grid {
-    fn new() -> Grid {
-        Grid {
-            grid: vec![false; 1000 * 1000],
-        }
+/// Part B: lights have a brightness of zero or more
+struct Brightness;
+
+impl Semantics for Brightness {
+    type Light = u32;
+
+    const EXTENSION: &str = "pgm";
+
+    fn on(light: &mut u32) {
+        *light += 1;
     }
 
-    fn execute(&mut self, instruction: &Instruction) {
-        match instruction.command {
-            Command::On => {
-                for x in instruction.sx..=instruction.ex {
-                    for y in instruction.sy..=instruction.ey {
-                        self.grid[1000 * y + x] = true
-                    }
-                }
-            }
-            Command::Off => {
-                for x in instruction.sx..=instruction.ex {
-                    for y in instruction.sy..=instruction.ey {
-                        self.grid[1000 * y + x] = false
-                    }
-                }
-            }
-            Command::Toggle => {
-                for x in instruction.sx..=instruction.ex {
-                    for y in instruction.sy..=instruction.ey {
-                        let val = self.grid[1000 * y + x];
-                        self.grid[1000 * y + x] = !val
-                    }
-                }
-            }
-        }
+    fn off(light: &mut u32) {
+        *light = light.saturating_sub(1);
     }
 
-    fn num_on(&self) -> usize {
-        let mut num_on: usize = 0;
-        for is_on in self.grid.iter() {
-            if *is_on {
-                num_on += 1
-            }
-        }
-        num_on
+    fn toggle(light: &mut u32) {
+        *light += 2;
+    }
+
+    fn value(light: &u32) -> u32 {
+        *light
+    }
+
+    fn write_image<W: Write>(
+        out: &mut W,
+        size: usize,
+        lights: &[u32],
+        peak: u32,
+    ) -> io::Result<()> {
+        let too_bright = |brightness: u32| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Brightness {brightness} is too large for an image"),
+            )
+        };
+        let max_value = u16::try_from(peak).map_err(|_| too_bright(peak))?;
+        let pixels = lights
+            .iter()
+            .map(|b| u16::try_from(*b).map_err(|_| too_bright(*b)))
+            .collect::<io::Result<Vec<u16>>>()?;
+        write_pgm(out, size, size, max_value, &pixels)
     }
 }
 
-struct Grid2 {
-    grid: Vec<Vec<u32>>,
+struct Grid<S: Semantics> {
+    size: usize,
+    lights: Vec<S::Light>,
 }
 
-impl Grid2 {
-    fn new() -> Grid2 {
-        Grid2 {
-            grid: vec![vec![0; 1000]; 1000],
+impl<S: Semantics> Grid<S> {
+    /// A square grid of lights, all starting off
+    fn new(size: usize) -> Grid<S> {
+        Grid {
+            size,
+            lights: vec![S::Light::default(); size * size],
         }
     }
 
     fn execute(&mut self, instruction: &Instruction) {
-        match instruction.command {
-            Command::On => {
-                let slicey = &mut self.grid[instruction.sy..=instruction.ey];
-                for y in slicey {
-                    let slicex = &mut y[instruction.sx..=instruction.ex];
-                    for x in slicex {
-                        *x += 1;
-                    }
-                }
-            }
-            Command::Off => {
-                let slicey = &mut self.grid[instruction.sy..=instruction.ey];
-                for y in slicey {
-                    let slicex = &mut y[instruction.sx..=instruction.ex];
-                    for x in slicex {
-                        if *x != 0 {
-                            *x -= 1;
-                        }
-                    }
-                }
-            }
-            Command::Toggle => {
-                let slicey = &mut self.grid[instruction.sy..=instruction.ey];
-                for y in slicey {
-                    let slicex = &mut y[instruction.sx..=instruction.ex];
-                    for x in slicex {
-                        *x += 2;
-                    }
-                }
-            }
+        let apply = match instruction.command {
+            Command::On => S::on,
+            Command::Off => S::off,
+            Command::Toggle => S::toggle,
+        };
+
+        for y in instruction.sy..=instruction.ey {
+            let row = self.size * y;
+            let slicex = &mut self.lights[row + instruction.sx..=row + instruction.ex];
+            slicex.iter_mut().for_each(apply);
         }
     }
 
-    fn total_brightness(&self) -> u32 {
-        self.grid.iter().flatten().sum()
+    fn total(&self) -> u32 {
+        self.lights.iter().map(S::value).sum()
+    }
+
+    fn peak(&self) -> u32 {
+        self.lights.iter().map(S::value).max().unwrap_or(0)
+    }
+
+    fn write_image(&self, path: &Path, peak: u32) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_to(&mut out, peak)?;
+        out.flush()
+    }
+
+    fn write_to<W: Write>(&self, out: &mut W, peak: u32) -> io::Result<()> {
+        S::write_image(out, self.size, &self.lights, peak)
     }
 }
 
-fn parse_input_line(s: &str) -> Instruction {
-    let pattern = r"(?<command>turn on|turn off|toggle) (?<sx>[0-9]+),(?<sy>[0-9]+) through (?<ex>[0-9]+),(?<ey>[0-9]+)";
-    let re = Regex::new(pattern).unwrap();
-    let c = re.captures(s).unwrap();
-
-    let command = c.name("command").unwrap().as_str();
-    let sx = c.name("sx").unwrap().as_str();
-    let sy = c.name("sy").unwrap().as_str();
-    let ex = c.name("ex").unwrap().as_str();
-    let ey = c.name("ey").unwrap().as_str();
-
-    Instruction::new(
-        Command::from(command),
-        sx.parse::<usize>().unwrap(),
-        sy.parse::<usize>().unwrap(),
-        ex.parse::<usize>().unwrap(),
-        ey.parse::<usize>().unwrap(),
-    )
+fn parse_input(input: &str) -> Vec<Instruction> {
+    Instruction::parser()
+        .parse_lines(input)
+        .expect("Invalid input")
 }
 
+const GRID_SIZE: usize = 1000;
+
+const RENDER_DIR_VAR: &str = "AOC_RENDER_DIR";
+
+const RENDER_FRAMES_VAR: &str = "AOC_RENDER_FRAMES";
+
+const INPUT_REGEX: &str = "^(?<command>turn on|turn off|toggle) (?<sx>[0-9]+),(?<sy>[0-9]+) through (?<ex>[0-9]+),(?<ey>[0-9]+)$";
+
 const INPUT_A: &str = indoc! {r#"
 turn off 660,55 through 986,197
 turn off 341,304 through 638,850
@@ -552,17 +647,46 @@ toggle 296,687 through 906,775"#};
 
 #[cfg(test)]
 mod tests {
-    use crate::year_2015::day_06::{part_a, part_b};
+    use crate::year_2015::day_06::{Brightness, Grid, INPUT_A, OnOff, parse_input, part_a, part_b};
+    use std::io;
 
     #[test]
     fn test_a() {
-        let result = part_a();
+        let result = part_a(&parse_input(INPUT_A));
         assert_eq!(result, 400410);
     }
 
     #[test]
     fn test_b() {
-        let result = part_b();
+        let result = part_b(&parse_input(INPUT_A));
         assert_eq!(result, 15343601);
     }
+
+    #[test]
+    fn test_images() {
+        let instructions = parse_input("turn on 0,0 through 2,0\ntoggle 9,0 through 9,1");
+        let mut grid: Grid<OnOff> = Grid::new(10);
+        instructions.iter().for_each(|i| grid.execute(i));
+        let mut out: Vec<u8> = Vec::new();
+        grid.write_to(&mut out, grid.peak()).unwrap();
+
+        // Each row packs into two bytes, with lit pixels as clear bits and the padding left clear
+        let (header, rows) = out.split_at(9);
+        assert_eq!(header, b"P4\n10 10\n");
+        assert_eq!(rows.len(), 20);
+        assert_eq!(rows[..4], [0x1f, 0x80, 0xff, 0x80]);
+        assert_eq!(rows[4..6], [0xff, 0xc0]);
+
+        let instructions = parse_input("toggle 0,0 through 1,0\nturn on 0,1 through 0,1");
+        let mut grid: Grid<Brightness> = Grid::new(3);
+        instructions.iter().for_each(|i| grid.execute(i));
+        let mut out: Vec<u8> = Vec::new();
+        grid.write_to(&mut out, grid.peak()).unwrap();
+        assert_eq!(out, b"P5\n3 3\n2\n\x02\x02\x00\x01\x00\x00\x00\x00\x00");
+
+        // A grayscale image only goes up to 65535
+        grid.lights[4] = 70000;
+        let error = grid.write_to(&mut Vec::new(), grid.peak()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
 }
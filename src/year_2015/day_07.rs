@@ -54,8 +54,8 @@
 /// (including wire a). What new signal is ultimately provided to wire a?
 ///
 /// Your puzzle answer was 2797.
+use crate::line_parser::line_parser;
 use indoc::indoc;
-use regex::Regex;
 use std::collections::HashMap;
 use std::time::SystemTime;
 
@@ -63,61 +63,59 @@ pub fn run() {
     println!("--- Day 7: Some Assembly Required ---");
 
     let now = SystemTime::now();
-    let answer_a = part_a();
+    let input_map = parse_input(INPUT_A);
+    let duration = now.elapsed().expect("Elapsed failed");
+    println!("Parsed the instructions booklet");
+    println!(" in {}ms", duration.as_millis());
+
+    let now = SystemTime::now();
+    let answer_a = part_a(&input_map);
     let duration = now.elapsed().expect("Elapsed failed");
     println!("In little Bobby's kit's instructions booklet, what signal is ultimately provided to wire a?");
     println!(" {}", answer_a);
     println!(" in {}ms", duration.as_millis());
 
     let now = SystemTime::now();
-    let answer_b = part_b();
+    let answer_b = part_b(&input_map);
     let duration = now.elapsed().expect("Elapsed failed");
     println!("Now, take the signal you got on wire a, override wire b to that signal, and reset the other wires (including wire a). What new signal is ultimately provided to wire a?");
     println!(" {}", answer_b);
     println!(" in {}ms", duration.as_millis());
 }
 
-fn part_a() -> u16 {
-    let dr = DayRegex::init();
-
-    let mut input_map: HashMap<String, Gate> = HashMap::new();
-    for line in INPUT_A.lines() {
-        if let Some((k, g)) = Gate::from(line, &dr) {
-            input_map.insert(k, g);
-        }
-    }
-
+fn part_a(input_map: &HashMap<String, Gate>) -> u16 {
     let mut eval_map: HashMap<String, u16> = HashMap::new();
     let wire = "a";
-    solve(&input_map, &mut eval_map, wire);
+    solve(input_map, &mut eval_map, wire);
     let p_answer = eval_map.get(wire).unwrap();
     *p_answer
 }
 
-fn part_b() -> u16 {
-    let dr = DayRegex::init();
-
-    let mut input_map: HashMap<String, Gate> = HashMap::new();
-    for line in INPUT_A.lines() {
-        if let Some((k, g)) = Gate::from(line, &dr) {
-            input_map.insert(k, g);
-        }
-    }
-
+fn part_b(input_map: &HashMap<String, Gate>) -> u16 {
     let mut eval_map: HashMap<String, u16> = HashMap::new();
-    solve(&input_map, &mut eval_map, "a");
+    solve(input_map, &mut eval_map, "a");
     let a_answer = *(eval_map.get("a").unwrap());
 
-    // Now create a new B value based on what A is. Override the input map with the new value
-    // for B, clear the eval map and solve again for a
-    let new_b = Gate::Value(a_answer);
-    input_map.insert(String::from("b"), new_b);
+    // Now override B with what A is, clear the eval map and solve again for a. Wires already in
+    // the eval map are never looked up in the input map, so putting B there overrides its gate
+    // without having to change the input map.
     eval_map.clear();
+    eval_map.insert(String::from("b"), a_answer);
 
-    solve(&input_map, &mut eval_map, "a");
+    solve(input_map, &mut eval_map, "a");
     *(eval_map.get("a").unwrap())
 }
 
+/// Reads the gate driving each wire
+fn parse_input(input: &str) -> HashMap<String, Gate> {
+    Connection::parser()
+        .parse_lines(input)
+        .expect("Invalid input")
+        .into_iter()
+        .map(Connection::into_gate)
+        .collect()
+}
+
 fn solve(input_map: &HashMap<String, Gate>, eval_map: &mut HashMap<String, u16>, wire: &str) {
     if !eval_map.contains_key(wire) {
         let gate = input_map
@@ -189,141 +187,50 @@ enum Gate {
     Not(String),
 }
 
-impl Gate {
-    fn from(s: &str, dr: &DayRegex) -> Option<(String, Gate)> {
-        let ret;
-        if let Some(g) = dr.parse_value(s) {
-            ret = Some(g);
-        } else if let Some(g) = dr.parse_wire(s) {
-            ret = Some(g);
-        } else if let Some(g) = dr.parse_and(s) {
-            ret = Some(g);
-        } else if let Some(g) = dr.parse_or(s) {
-            ret = Some(g);
-        } else if let Some(g) = dr.parse_lshift(s) {
-            ret = Some(g);
-        } else if let Some(g) = dr.parse_rshift(s) {
-            ret = Some(g);
-        } else if let Some(g) = dr.parse_not(s) {
-            ret = Some(g);
-        } else {
-            ret = None;
-        };
-        ret
+line_parser! {
+    /// A line of the booklet, connecting a gate to the wire it drives
+    enum Connection {
+        // 123 -> x
+        Value { value: u16, key: String } = r"^(?<value>[0-9]+) -> (?<key>[a-z]+)$",
+        // y -> x
+        Wire { wire: String, key: String } = r"^(?<wire>[a-z]+) -> (?<key>[a-z]+)$",
+        // lf AND lq -> ls
+        And { wire_a: String, wire_b: String, key: String } =
+            r"^(?<wire_a>[0-9a-z]+) AND (?<wire_b>[0-9a-z]+) -> (?<key>[a-z]+)$",
+        // kl OR kr -> ks
+        Or { wire_a: String, wire_b: String, key: String } =
+            r"^(?<wire_a>[0-9a-z]+) OR (?<wire_b>[0-9a-z]+) -> (?<key>[a-z]+)$",
+        // fj LSHIFT 15 -> fn
+        LShift { wire: String, value: u16, key: String } =
+            r"^(?<wire>[a-z]+) LSHIFT (?<value>[0-9]+) -> (?<key>[a-z]+)$",
+        // fj RSHIFT 15 -> fn
+        RShift { wire: String, value: u16, key: String } =
+            r"^(?<wire>[a-z]+) RSHIFT (?<value>[0-9]+) -> (?<key>[a-z]+)$",
+        // NOT e -> f
+        Not { wire: String, key: String } = r"^NOT (?<wire>[a-z]+) -> (?<key>[a-z]+)$",
     }
 }
 
-struct DayRegex {
-    value: Regex,
-    wire: Regex,
-    and: Regex,
-    or: Regex,
-    lshift: Regex,
-    rshift: Regex,
-    not: Regex,
-}
-
-impl DayRegex {
-    fn init() -> DayRegex {
-        DayRegex {
-            value: Regex::new(r"^(?<value>[0-9]+) -> (?<key>[a-z]+)$").unwrap(), // 123 -> x
-            wire: Regex::new(r"^(?<wire>[a-z]+) -> (?<key>[a-z]+)$").unwrap(),   // y -> x
-            and: Regex::new(r"^(?<wire_a>[0-9a-z]+) AND (?<wire_b>[0-9a-z]+) -> (?<key>[a-z]+)$")
-                .unwrap(), // lf AND lq -> ls
-            or: Regex::new(r"^(?<wire_a>[0-9a-z]+) OR (?<wire_b>[0-9a-z]+) -> (?<key>[a-z]+)$")
-                .unwrap(), // kl OR kr -> ks
-            lshift: Regex::new(r"^(?<wire>[a-z]+) LSHIFT (?<value>[0-9]+) -> (?<key>[a-z]+)$")
-                .unwrap(), // fj LSHIFT 15 -> fn
-            rshift: Regex::new(r"^(?<wire>[a-z]+) RSHIFT (?<value>[0-9]+) -> (?<key>[a-z]+)$")
-                .unwrap(), // fj RSHIFT 15 -> fn
-            not: Regex::new(r"^NOT (?<wire>[a-z]+) -> (?<key>[a-z]+)$").unwrap(),
+impl Connection {
+    fn into_gate(self) -> (String, Gate) {
+        match self {
+            Connection::Value { value, key } => (key, Gate::Value(value)),
+            Connection::Wire { wire, key } => (key, Gate::Wire(wire)),
+            Connection::And {
+                wire_a,
+                wire_b,
+                key,
+            } => (key, Gate::And(wire_a, wire_b)),
+            Connection::Or {
+                wire_a,
+                wire_b,
+                key,
+            } => (key, Gate::Or(wire_a, wire_b)),
+            Connection::LShift { wire, value, key } => (key, Gate::LShift(wire, value)),
+            Connection::RShift { wire, value, key } => (key, Gate::RShift(wire, value)),
+            Connection::Not { wire, key } => (key, Gate::Not(wire)),
         }
     }
-
-    fn parse_value(&self, s: &str) -> Option<(String, Gate)> {
-        self.value.captures(s).map(|c| {
-            let value = c.name("value").unwrap().as_str();
-            let key = c.name("key").unwrap().as_str();
-
-            let k = String::from(key);
-            let v: u16 = value.parse().unwrap();
-            (k, Gate::Value(v))
-        })
-    }
-
-    fn parse_wire(&self, s: &str) -> Option<(String, Gate)> {
-        self.wire.captures(s).map(|c| {
-            let value = c.name("wire").unwrap().as_str();
-            let key = c.name("key").unwrap().as_str();
-
-            let k = String::from(key);
-            let w = String::from(value);
-            (k, Gate::Wire(w))
-        })
-    }
-
-    fn parse_and(&self, s: &str) -> Option<(String, Gate)> {
-        self.and.captures(s).map(|c| {
-            let wire_a = c.name("wire_a").unwrap().as_str();
-            let wire_b = c.name("wire_b").unwrap().as_str();
-            let key = c.name("key").unwrap().as_str();
-
-            let k = String::from(key);
-            let wa = String::from(wire_a);
-            let wb = String::from(wire_b);
-            (k, Gate::And(wa, wb))
-        })
-    }
-
-    fn parse_or(&self, s: &str) -> Option<(String, Gate)> {
-        self.or.captures(s).map(|c| {
-            let wire_a = c.name("wire_a").unwrap().as_str();
-            let wire_b = c.name("wire_b").unwrap().as_str();
-            let key = c.name("key").unwrap().as_str();
-
-            let k = String::from(key);
-            let wa = String::from(wire_a);
-            let wb = String::from(wire_b);
-            (k, Gate::Or(wa, wb))
-        })
-    }
-
-    fn parse_lshift(&self, s: &str) -> Option<(String, Gate)> {
-        self.lshift.captures(s).map(|c| {
-            let wire = c.name("wire").unwrap().as_str();
-            let value = c.name("value").unwrap().as_str();
-            let key = c.name("key").unwrap().as_str();
-
-            let k = String::from(key);
-            let w = String::from(wire);
-            let v: u16 = value.parse().unwrap();
-            (k, Gate::LShift(w, v))
-        })
-    }
-
-    fn parse_rshift(&self, s: &str) -> Option<(String, Gate)> {
-        self.rshift.captures(s).map(|c| {
-            let wire = c.name("wire").unwrap().as_str();
-            let value = c.name("value").unwrap().as_str();
-            let key = c.name("key").unwrap().as_str();
-
-            let k = String::from(key);
-            let w = String::from(wire);
-            let v: u16 = value.parse().unwrap();
-            (k, Gate::RShift(w, v))
-        })
-    }
-
-    fn parse_not(&self, s: &str) -> Option<(String, Gate)> {
-        self.not.captures(s).map(|c| {
-            let value = c.name("wire").unwrap().as_str();
-            let key = c.name("key").unwrap().as_str();
-
-            let k = String::from(key);
-            let w = String::from(value);
-            (k, Gate::Not(w))
-        })
-    }
 }
 
 const INPUT_A: &str = indoc! {r#"
@@ -669,17 +576,17 @@ he RSHIFT 5 -> hh"#};
 
 #[cfg(test)]
 mod tests {
-    use crate::year_2015::day_07::{part_a, part_b};
+    use crate::year_2015::day_07::{INPUT_A, parse_input, part_a, part_b};
 
     #[test]
     fn test_a() {
-        let result = part_a();
+        let result = part_a(&parse_input(INPUT_A));
         assert_eq!(result, 16076);
     }
 
     #[test]
     fn test_b() {
-        let result = part_b();
+        let result = part_b(&parse_input(INPUT_A));
         assert_eq!(result, 2797);
     }
 }
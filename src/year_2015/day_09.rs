@@ -42,8 +42,8 @@
 ///
 /// Your puzzle answer was 909.
 use crate::algorithm::heap_permutations;
+use crate::line_parser::line_parser;
 use indoc::indoc;
-use regex::Regex;
 use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
 use std::time::SystemTime;
@@ -52,76 +52,76 @@ pub fn run() {
     println!("--- Day 9: All in a Single Night ---");
 
     let now = SystemTime::now();
-    let answer_a = part_a();
+    let map = parse_input(INPUT_A);
+    let duration = now.elapsed().expect("Elapsed failed");
+    println!("Parsed the distances");
+    println!(" in {}ms", duration.as_millis());
+
+    let now = SystemTime::now();
+    let answer_a = part_a(&map);
     let duration = now.elapsed().expect("Elapsed failed");
     println!("What is the distance of the shortest route?");
     println!(" {}", answer_a);
     println!(" in {}ms", duration.as_millis());
 
     let now = SystemTime::now();
-    let answer_b = part_b();
+    let answer_b = part_b(&map);
     let duration = now.elapsed().expect("Elapsed failed");
     println!("What is the distance of the longest route?");
     println!(" {}", answer_b);
     println!(" in {}ms", duration.as_millis());
 }
 
-fn part_a() -> u32 {
-    let dr = DayRegex::init();
-
-    let mut all_points: HashSet<String> = HashSet::new();
-    let mut distances: HashMap<(String, String), u32> = HashMap::new();
-
-    for line in INPUT_A.lines() {
-        if let Some(d) = Distance::from(line, &dr) {
-            all_points.insert(d.point_a.clone());
-            all_points.insert(d.point_b.clone());
-            distances.insert((d.point_a.clone(), d.point_b.clone()), d.distance);
-            distances.insert((d.point_b.clone(), d.point_a.clone()), d.distance);
-        }
-    }
-
+fn part_a(map: &Map) -> u32 {
     // Create all possible route permutations
-    let permutations = heap_permutations(&all_points);
+    let permutations = heap_permutations(&map.points);
 
     // Find the shortest distance
     let mut answer = u32::MAX;
     for perm in permutations {
-        let x = calc_distance(&perm, &distances);
+        let x = calc_distance(&perm, &map.distances);
         answer = min(answer, x);
     }
 
     answer
 }
 
-fn part_b() -> u32 {
-    let dr = DayRegex::init();
-
-    let mut all_points: HashSet<String> = HashSet::new();
-    let mut distances: HashMap<(String, String), u32> = HashMap::new();
-
-    for line in INPUT_A.lines() {
-        if let Some(d) = Distance::from(line, &dr) {
-            all_points.insert(d.point_a.clone());
-            all_points.insert(d.point_b.clone());
-            distances.insert((d.point_a.clone(), d.point_b.clone()), d.distance);
-            distances.insert((d.point_b.clone(), d.point_a.clone()), d.distance);
-        }
-    }
-
+fn part_b(map: &Map) -> u32 {
     // Create all possible route permutations
-    let permutations = heap_permutations(&all_points);
+    let permutations = heap_permutations(&map.points);
 
     // Find the longest distance
     let mut answer = 0;
     for perm in permutations {
-        let x = calc_distance(&perm, &distances);
+        let x = calc_distance(&perm, &map.distances);
         answer = max(answer, x);
     }
 
     answer
 }
 
+/// Every location, and the distance between each pair of them in both directions
+struct Map {
+    points: HashSet<String>,
+    distances: HashMap<(String, String), u32>,
+}
+
+fn parse_input(input: &str) -> Map {
+    let mut points: HashSet<String> = HashSet::new();
+    let mut distances: HashMap<(String, String), u32> = HashMap::new();
+
+    let lines = Distance::parser()
+        .parse_lines(input)
+        .expect("Invalid input");
+    for d in lines {
+        points.insert(d.point_a.clone());
+        points.insert(d.point_b.clone());
+        distances.insert((d.point_a.clone(), d.point_b.clone()), d.distance);
+        distances.insert((d.point_b.clone(), d.point_a.clone()), d.distance);
+    }
+    Map { points, distances }
+}
+
 fn calc_distance(points: &Vec<&str>, distances: &HashMap<(String, String), u32>) -> u32 {
     let mut total = 0;
     for ab in points.windows(2) {
@@ -132,45 +132,11 @@ fn calc_distance(points: &Vec<&str>, distances: &HashMap<(String, String), u32>)
     total
 }
 
-struct Distance {
-    point_a: String,
-    point_b: String,
-    distance: u32,
-}
-
-impl Distance {
-    fn from(s: &str, dr: &DayRegex) -> Option<Distance> {
-        dr.parse_distance(s)
-    }
-}
-
-struct DayRegex {
-    distance: Regex,
-}
-
-impl DayRegex {
-    fn init() -> DayRegex {
-        DayRegex {
-            distance: Regex::new(r"^(?<a>[a-zA-Z]+) to (?<b>[a-zA-Z]+) = (?<d>[0-9]+)$").unwrap(),
-        }
-    }
-
-    fn parse_distance(&self, s: &str) -> Option<Distance> {
-        self.distance.captures(s).map(|c| {
-            let a = c.name("a").unwrap().as_str();
-            let b = c.name("b").unwrap().as_str();
-            let d = c.name("d").unwrap().as_str();
-
-            let point_a = String::from(a);
-            let point_b = String::from(b);
-            let distance = d.parse::<u32>().unwrap();
-
-            Distance {
-                point_a,
-                point_b,
-                distance,
-            }
-        })
+line_parser! {
+    struct Distance = r"^(?<point_a>[a-zA-Z]+) to (?<point_b>[a-zA-Z]+) = (?<distance>[0-9]+)$" {
+        point_a: String,
+        point_b: String,
+        distance: u32,
     }
 }
 
@@ -211,17 +177,17 @@ AlphaCentauri to Arbre = 46"#};
 
 #[cfg(test)]
 mod tests {
-    use crate::year_2015::day_09::{part_a, part_b};
+    use crate::year_2015::day_09::{INPUT_A, parse_input, part_a, part_b};
 
     #[test]
     fn test_a() {
-        let result = part_a();
+        let result = part_a(&parse_input(INPUT_A));
         assert_eq!(result, 117);
     }
 
     #[test]
     fn test_b() {
-        let result = part_b();
+        let result = part_b(&parse_input(INPUT_A));
         assert_eq!(result, 909);
     }
 }
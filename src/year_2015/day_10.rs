@@ -28,6 +28,8 @@
 /// the length of the new result?
 ///
 /// Your puzzle answer was 6989950.
+use crate::look_say::length_after;
+use num_bigint::BigUint;
 use std::time::SystemTime;
 
 pub fn run() {
@@ -48,26 +50,23 @@ pub fn run() {
     println!(" in {}ms", duration.as_millis());
 }
 
-fn part_a() -> usize {
-    let mut say = String::from(INPUT_A);
-    for _ in 0..40 {
-        let look = parse_digits(&say);
-        say = look_say(look);
-    }
-
-    say.len()
+fn part_a() -> BigUint {
+    conway_length(40)
 }
 
-fn part_b() -> usize {
-    let mut say = String::from(INPUT_A);
-    for _ in 0..50 {
-        let look = parse_digits(&say);
-        say = look_say(look);
-    }
+fn part_b() -> BigUint {
+    conway_length(50)
+}
 
-    say.len()
+/// The length is kept as a big integer, since it grows by about 30% a step and soon outgrows any
+/// machine word.
+fn conway_length(iterations: usize) -> BigUint {
+    length_after(INPUT_A, iterations).expect("Input never splits into Conway's elements")
 }
 
+/// The original brute force step, kept to cross check the simulation and the lengths worked out
+/// from Conway's elements
+#[cfg(test)]
 fn parse_digits(look: &str) -> Vec<u32> {
     let ret: Result<Vec<u32>, String> = look
         .chars()
@@ -79,6 +78,7 @@ fn parse_digits(look: &str) -> Vec<u32> {
     ret.unwrap()
 }
 
+#[cfg(test)]
 fn look_say(look: Vec<u32>) -> String {
     let size = look.len();
     let mut i = 0;
@@ -100,17 +100,35 @@ const INPUT_A: &str = "1321131112";
 
 #[cfg(test)]
 mod tests {
-    use crate::year_2015::day_10::{part_a, part_b};
+    use crate::look_say::Simulation;
+    use crate::year_2015::day_10::{
+        INPUT_A, conway_length, look_say, parse_digits, part_a, part_b,
+    };
+    use num_bigint::BigUint;
 
     #[test]
     fn test_a() {
         let result = part_a();
-        assert_eq!(result, 492982);
+        assert_eq!(result, BigUint::from(492982u32));
     }
 
     #[test]
     fn test_b() {
         let result = part_b();
-        assert_eq!(result, 6989950);
+        assert_eq!(result, BigUint::from(6989950u32));
+    }
+
+    #[test]
+    fn test_brute_force() {
+        // Building the whole sequence must agree with the simulation and with the lengths worked
+        // out from Conway's elements at every step
+        let mut say = String::from(INPUT_A);
+        let mut simulation = Simulation::new(INPUT_A).unwrap();
+        for iterations in 0..=40 {
+            assert_eq!(simulation.digits(), say.as_bytes());
+            assert_eq!(BigUint::from(say.len()), conway_length(iterations));
+            say = look_say(parse_digits(&say));
+            simulation.step();
+        }
     }
 }
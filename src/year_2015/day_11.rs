@@ -60,139 +60,188 @@ pub fn run() {
 }
 
 fn part_a() -> String {
-    next_password(INPUT_A)
+    Policy::corporate().next_password(INPUT_A).unwrap()
 }
 
 fn part_b() -> String {
-    next_password("hepxxyzz")
+    // The password after the next one
+    Policy::corporate().passwords_after(INPUT_A).nth(1).unwrap()
 }
 
-fn next_password(current: &str) -> String {
-    let sorted_invalids = vec![
-        ascii_char_to_radix26("i"),
-        ascii_char_to_radix26("l"),
-        ascii_char_to_radix26("o"),
-    ];
-
-    let mut r26 = str_to_u64(current, 26, 97);
-    // Bump to next candidate
-    r26 += 1;
-    let mut vd = u64_to_reverse_digits(r26, 26);
-    let mut found = false;
-    while !found {
-        if check_contains_straight(&vd, 3)
-            && check_consecutive_duplicates(&vd, 2)
-            && check_contains_valid(&vd, &sorted_invalids)
-        {
-            found = true
-        } else {
-            r26 += 1;
-            vd = u64_to_reverse_digits(r26, 26);
+/// A single requirement a password must meet
+enum Rule {
+    /// None of these letters may appear
+    Forbidden(Vec<char>),
+    /// Must include an increasing straight of at least this many letters, without skipping any
+    Straight(usize),
+    /// Must contain at least this many different, non-overlapping pairs of letters
+    Pairs(usize),
+}
+
+impl Rule {
+    fn check(&self, policy: &Policy, digits: &[usize]) -> bool {
+        match self {
+            Rule::Forbidden(_) => self.first_forbidden(policy, digits).is_none(),
+            Rule::Straight(length) => check_contains_straight(digits, *length),
+            Rule::Pairs(count) => check_distinct_pairs(digits, *count),
         }
     }
 
-    reverse_digits_to_string(&vd)
+    /// Returns the position of the leftmost forbidden letter, if there is one
+    fn first_forbidden(&self, policy: &Policy, digits: &[usize]) -> Option<usize> {
+        match self {
+            Rule::Forbidden(letters) => digits
+                .iter()
+                .position(|d| letters.contains(&policy.alphabet[*d])),
+            _ => None,
+        }
+    }
 }
 
-fn str_to_u64(s: &str, radix: u64, ascii_base: u8) -> u64 {
-    let zero: u64 = 0;
-    // Assuming each character is an ASCII character byte, simply subtract all of the
-    // characters before the ascii_base. Fold over each number to build up number.
-    s.bytes()
-        .map(|c| c - ascii_base)
-        .fold(zero, |a, x| (a * radix) + u64::from(x))
+/// Which letters a password is made of, how long it is, and the rules it must follow. Passwords
+/// are handled as digits, where each letter is its position in the alphabet.
+struct Policy {
+    alphabet: Vec<char>,
+    length: usize,
+    rules: Vec<Rule>,
 }
 
-fn ascii_char_to_radix26(s: &str) -> u8 {
-    if (s.len() != 1) || (!s.is_ascii()) {
-        panic!("String is not a single ASCII char")
+impl Policy {
+    /// The policy from the puzzle, with the new Security-Elf's requirements
+    fn corporate() -> Policy {
+        Policy {
+            alphabet: ('a'..='z').collect(),
+            length: 8,
+            rules: vec![
+                Rule::Straight(3),
+                Rule::Forbidden(vec!['i', 'o', 'l']),
+                Rule::Pairs(2),
+            ],
+        }
     }
 
-    let c = s.as_bytes().first().unwrap();
-    c - 97
-}
+    /// Returns the next valid password after the current one, or None if there are no more
+    /// passwords of the right length
+    fn next_password(&self, current: &str) -> Option<String> {
+        self.passwords_after(current).next()
+    }
 
-fn u64_to_reverse_digits(n: u64, radix: u64) -> Vec<u8> {
-    // Returned Vec will have digits in reverse order.
-    let mut ret: Vec<u8> = Vec::new();
+    /// Returns an iterator over every valid password after the current one, in order. Nothing is
+    /// searched for until the next password is asked for.
+    fn passwords_after(&self, current: &str) -> Passwords<'_> {
+        Passwords {
+            policy: self,
+            digits: self.str_to_digits(current),
+        }
+    }
 
-    let mut num: u64 = n;
-    loop {
-        let m: u8 = (num % radix) as u8;
-        num /= radix;
+    fn str_to_digits(&self, s: &str) -> Option<Vec<usize>> {
+        // An empty password has nothing to increment, so there are no passwords after it
+        if self.length == 0 || s.chars().count() != self.length {
+            return None;
+        }
+        s.chars()
+            .map(|c| self.alphabet.iter().position(|x| *x == c))
+            .collect()
+    }
 
-        ret.push(m);
-        if num == 0 {
-            break;
+    fn digits_to_string(&self, digits: &[usize]) -> String {
+        digits.iter().map(|d| self.alphabet[*d]).collect()
+    }
+
+    /// Increments the digit at `pos`, carrying into the digits to its left, and resets every digit
+    /// to its right to the first letter. Returns false if the carry runs off the end.
+    fn increment(&self, digits: &mut [usize], pos: usize) -> bool {
+        digits[pos + 1..].iter_mut().for_each(|d| *d = 0);
+        for d in digits[..=pos].iter_mut().rev() {
+            *d += 1;
+            if *d < self.alphabet.len() {
+                return true;
+            }
+            *d = 0;
         }
+        false
     }
-    ret
 }
 
-fn reverse_digits_to_string(vd: &[u8]) -> String {
-    vd.iter()
-        .rev()
-        .map(|x| char::from_u32(u32::from(*x) + 97).unwrap())
-        .collect()
+/// Iterator returned by `Policy::passwords_after`
+struct Passwords<'a> {
+    policy: &'a Policy,
+    digits: Option<Vec<usize>>,
 }
 
-fn check_contains_straight(vd: &[u8], count: i32) -> bool {
-    let mut c = 1;
-    let mut last: u8 = u8::MAX - 1;
-    for x in vd.iter().rev() {
-        if *x == last + 1 {
-            c += 1;
-            if c == count {
-                break;
+impl Iterator for Passwords<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let policy = self.policy;
+        let digits = self.digits.as_mut()?;
+
+        // Bump to next candidate
+        let mut pos = digits.len() - 1;
+        while policy.increment(digits, pos) {
+            // Rather than counting through every password with a forbidden letter in it, skip
+            // straight past all of them by bumping that letter and resetting the ones after it
+            let forbidden = policy
+                .rules
+                .iter()
+                .filter_map(|r| r.first_forbidden(policy, digits))
+                .min();
+            if let Some(p) = forbidden {
+                pos = p;
+                continue;
             }
-        } else {
-            c = 1
+
+            if policy.rules.iter().all(|r| r.check(policy, digits)) {
+                return Some(policy.digits_to_string(digits));
+            }
+            pos = digits.len() - 1;
         }
-        last = *x;
+
+        // Every password has been tried
+        self.digits = None;
+        None
     }
-    c == count
 }
 
-fn check_consecutive_duplicates(vd: &[u8], num_dupes: i32) -> bool {
-    // Keep a count of how many duplicates we have encountered.
-    let mut dupes = 0;
-
-    // Set the last element to something invalid
-    let mut last: u8 = u8::MAX - 1;
-    for x in vd.iter().rev() {
-        if *x == last {
-            // If the current element matches the last one, we have a duplicate. Reset the last to
-            // invalid so we do not count overlapping duplicates.
-            dupes += 1;
-            last = u8::MAX - 1;
-            if dupes == num_dupes {
-                break;
-            }
+fn check_contains_straight(digits: &[usize], length: usize) -> bool {
+    let mut c = 1;
+    for ab in digits.windows(2) {
+        if ab[1] == ab[0] + 1 {
+            c += 1;
         } else {
-            last = *x;
+            c = 1
+        }
+        if c >= length {
+            return true;
         }
     }
-    dupes == num_dupes
+    c >= length
 }
 
-fn check_contains_valid(vd: &[u8], sorted_invalids: &[u8]) -> bool {
-    if sorted_invalids.is_empty() {
-        return true;
-    }
-
-    for x in vd.iter() {
-        if sorted_invalids.binary_search(x).is_ok() {
-            return false;
+fn check_distinct_pairs(digits: &[usize], count: usize) -> bool {
+    // Pairs of the same letter only count once, and a pair uses up both of its letters so that
+    // overlapping pairs like aaa only count once
+    let mut pairs: Vec<usize> = Vec::new();
+    let mut i = 0;
+    while i + 1 < digits.len() {
+        if digits[i] == digits[i + 1] {
+            if !pairs.contains(&digits[i]) {
+                pairs.push(digits[i]);
+            }
+            i += 2;
+        } else {
+            i += 1;
         }
     }
-    true
+    pairs.len() >= count
 }
 
 const INPUT_A: &str = "hepxcrrq";
 
 #[cfg(test)]
 mod tests {
-    use crate::year_2015::day_11::{part_a, part_b};
+    use crate::year_2015::day_11::{Policy, Rule, part_a, part_b};
 
     #[test]
     fn test_a() {
@@ -205,4 +254,56 @@ mod tests {
         let answer = part_b();
         assert_eq!("heqaabcc", answer);
     }
+
+    #[test]
+    fn test_examples() {
+        let policy = Policy::corporate();
+        assert_eq!(policy.next_password("abcdefgh").unwrap(), "abcdffaa");
+        assert_eq!(policy.next_password("ghijklmn").unwrap(), "ghjaabcc");
+        assert_eq!(policy.next_password("zzzzzzzz"), None);
+    }
+
+    #[test]
+    fn test_long_passwords() {
+        // Far too many letters to fit in a u64
+        let policy = Policy {
+            length: 20,
+            ..Policy::corporate()
+        };
+        let passwords: Vec<String> = policy
+            .passwords_after("hepxcrrqhepxcrrqzzzz")
+            .take(3)
+            .collect();
+        assert_eq!(
+            passwords,
+            vec![
+                "hepxcrrqhepxcrrraabc",
+                "hepxcrrqhepxcrrrabcc",
+                "hepxcrrqhepxcrrrbbcd"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alphabet() {
+        let policy = Policy {
+            alphabet: "0123456789".chars().collect(),
+            length: 4,
+            rules: vec![Rule::Straight(4)],
+        };
+        let passwords: Vec<String> = policy.passwords_after("0000").collect();
+        assert_eq!(
+            passwords,
+            vec!["0123", "1234", "2345", "3456", "4567", "5678", "6789"]
+        );
+    }
+
+    #[test]
+    fn test_empty_passwords() {
+        let policy = Policy {
+            length: 0,
+            ..Policy::corporate()
+        };
+        assert_eq!(policy.next_password(""), None);
+    }
 }
@@ -36,91 +36,438 @@
 /// What is the sum of all numbers in the document (ignoring red)?
 ///
 /// Your puzzle answer was 68466.
-use serde_json::Value;
+use num_bigint::BigInt;
+use regex::Regex;
+use serde_json::{Number, Value};
+use std::env;
+use std::fmt;
+use std::ops::Add;
 use std::time::SystemTime;
 
 pub fn run() {
     println!("--- Day 12: JSAbacusFramework.io ---");
 
+    let mode = if env::var(DECIMAL_VAR).is_ok() {
+        Mode::Decimal
+    } else {
+        Mode::Integer
+    };
+
     let now = SystemTime::now();
-    let answer_a = part_a();
+    let answer_a = part_a(mode);
     let duration = now.elapsed().expect("Elapsed failed");
     println!("What is the sum of all numbers in the document?");
     println!(" {}", answer_a);
     println!(" in {}ms", duration.as_millis());
 
     let now = SystemTime::now();
-    let answer_b = part_b();
+    let answer_b = part_b(mode);
     let duration = now.elapsed().expect("Elapsed failed");
     println!("What is the sum of all numbers in the document (ignoring red)?");
     println!(" {}", answer_b);
     println!(" in {}ms", duration.as_millis());
 }
 
-fn part_a() -> i64 {
+fn part_a(mode: Mode) -> Decimal {
     let v: Value = serde_json::from_str(INPUT_A).unwrap();
-    walk_numbers(&v)
+    sum_numbers(&v, &[], mode).unwrap()
 }
 
-fn part_b() -> i64 {
+fn part_b(mode: Mode) -> Decimal {
     let v: Value = serde_json::from_str(INPUT_A).unwrap();
-    walk_numbers_non_red(&v)
+    let filters = Filter::parse(r#"skip-objects-with "red""#).unwrap();
+    sum_numbers(&v, &filters, mode).unwrap()
+}
+
+/// Decides which parts of the document are counted. Filters are written as text, one per
+/// statement separated by semicolons:
+///
+/// - `skip-objects-with <json>` ignores any object, and all of its children, which has any
+///   property with the given value.
+/// - `only-arrays` only counts numbers that are directly inside an array.
+/// - `keys <regex>` only follows properties of objects whose key matches.
+/// - `max-depth <n>` ignores numbers nested inside more than n arrays and objects.
+#[derive(Debug)]
+enum Filter {
+    SkipObjectsWith(Value),
+    OnlyArrays,
+    Keys(Regex),
+    MaxDepth(usize),
+}
+
+/// What a value is directly inside of
+#[derive(Clone, Copy, PartialEq)]
+enum Parent {
+    Root,
+    Array,
+    Object,
 }
 
-fn walk_numbers(v: &Value) -> i64 {
-    fn kv_walk_numbers(kv: (&String, &Value)) -> i64 {
-        walk_numbers(kv.1)
+impl Filter {
+    fn parse(s: &str) -> Result<Vec<Filter>, String> {
+        s.split(';')
+            .map(str::trim)
+            .filter(|statement| !statement.is_empty())
+            .map(|statement| {
+                let (name, arg) = statement.split_once(' ').unwrap_or((statement, ""));
+                let arg = arg.trim();
+                match name {
+                    "skip-objects-with" => serde_json::from_str(arg)
+                        .map(Filter::SkipObjectsWith)
+                        .map_err(|e| format!("Invalid value {arg:?}: {e}")),
+                    "only-arrays" => Ok(Filter::OnlyArrays),
+                    "keys" => Regex::new(arg)
+                        .map(Filter::Keys)
+                        .map_err(|e| format!("Invalid regex {arg:?}: {e}")),
+                    "max-depth" => arg
+                        .parse::<usize>()
+                        .map(Filter::MaxDepth)
+                        .map_err(|_| format!("Invalid depth {arg:?}")),
+                    _ => Err(format!("Unknown filter: {name}")),
+                }
+            })
+            .collect()
     }
 
-    match v {
-        Value::Number(num) => num.as_i64().unwrap(),
-        Value::Array(arr) => arr.iter().map(walk_numbers).sum(),
-        Value::Object(obj) => obj.into_iter().map(kv_walk_numbers).sum(),
-        _ => 0,
+    /// Whether an array or object at this depth should be walked into
+    fn enters(&self, v: &Value, depth: usize) -> bool {
+        match (self, v) {
+            (Filter::SkipObjectsWith(skip), Value::Object(obj)) => !obj.values().any(|x| x == skip),
+            (Filter::MaxDepth(max), _) => depth < *max,
+            _ => true,
+        }
+    }
+
+    /// Whether the property with this key should be walked into
+    fn follows(&self, key: &str) -> bool {
+        match self {
+            Filter::Keys(re) => re.is_match(key),
+            _ => true,
+        }
+    }
+
+    /// Whether a number directly inside this parent should be counted
+    fn counts(&self, parent: Parent) -> bool {
+        match self {
+            Filter::OnlyArrays => parent == Parent::Array,
+            _ => true,
+        }
+    }
+}
+
+/// How the numbers in the document are summed
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    /// Every counted number must be a whole number, although it may be of any size and written
+    /// with a fraction or exponent, such as 2.0 or 1e3
+    Integer,
+    /// Any number may be counted, and fractions are summed exactly. Used when the
+    /// `AOC_JSON_DECIMAL` environment variable is set.
+    Decimal,
+}
+
+const DECIMAL_VAR: &str = "AOC_JSON_DECIMAL";
+
+/// Largest power of ten a number may be scaled by. Every digit of the sum is kept, so this stops a
+/// single number like 1e999999999 from needing gigabytes.
+const MAX_EXPONENT: i64 = 10_000;
+
+/// An exact decimal number, equal to `mantissa` / 10^`scale`. It is kept normalized, with no
+/// trailing zeros after the decimal point, so integers always have a scale of 0.
+#[derive(Clone, Debug, PartialEq)]
+struct Decimal {
+    mantissa: BigInt,
+    scale: u32,
+}
+
+impl Decimal {
+    fn zero() -> Decimal {
+        Decimal {
+            mantissa: BigInt::ZERO,
+            scale: 0,
+        }
+    }
+
+    /// Reads a number from its JSON text. With the `exact-json` feature serde_json keeps the
+    /// original text of every number, so nothing is lost to rounding through u64, i64 or f64
+    /// first. Without it, numbers that don't fit in a u64 or i64 have already been rounded.
+    fn parse(num: &Number) -> Result<Decimal, String> {
+        let text = num.to_string();
+        let invalid = || format!("Invalid number {text}");
+
+        let (significand, exponent) = match text.split_once(['e', 'E']) {
+            Some((significand, exponent)) => {
+                (significand, exponent.parse::<i64>().map_err(|_| invalid())?)
+            }
+            None => (text.as_str(), 0),
+        };
+        let (int, frac) = significand.split_once('.').unwrap_or((significand, ""));
+        let digits = format!("{int}{frac}");
+        let mantissa: BigInt = digits.parse().map_err(|_| invalid())?;
+        if mantissa == BigInt::ZERO {
+            // However large the exponent, zero takes no digits to hold
+            return Ok(Decimal::zero());
+        }
+
+        let exponent = exponent - frac.len() as i64;
+        if exponent.abs() > MAX_EXPONENT {
+            return Err(format!("Number {text} is too large to sum exactly"));
+        }
+        let decimal = if exponent >= 0 {
+            Decimal {
+                mantissa: mantissa * BigInt::from(10).pow(exponent as u32),
+                scale: 0,
+            }
+        } else {
+            Decimal {
+                mantissa,
+                scale: -exponent as u32,
+            }
+        };
+        Ok(decimal.normalize())
+    }
+
+    fn normalize(mut self) -> Decimal {
+        let ten = BigInt::from(10);
+        while self.scale > 0 && (&self.mantissa % &ten) == BigInt::ZERO {
+            self.mantissa /= &ten;
+            self.scale -= 1;
+        }
+        self
+    }
+
+    /// The mantissa this number would have with a larger scale
+    fn mantissa_at(self, scale: u32) -> BigInt {
+        self.mantissa * BigInt::from(10).pow(scale - self.scale)
+    }
+
+    fn is_integer(&self) -> bool {
+        self.scale == 0
+    }
+
+    /// The sum as an i64, if it is a whole number that fits
+    #[cfg(test)]
+    fn to_i64(&self) -> Option<i64> {
+        if self.is_integer() {
+            i64::try_from(&self.mantissa).ok()
+        } else {
+            None
+        }
     }
 }
 
-fn walk_numbers_non_red(v: &Value) -> i64 {
-    fn kv_walk_numbers_non_red(kv: (&String, &Value)) -> i64 {
-        walk_numbers_non_red(kv.1)
+impl Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: Decimal) -> Decimal {
+        // Line up the decimal points before adding the mantissas
+        let scale = u32::max(self.scale, other.scale);
+        Decimal {
+            mantissa: self.mantissa_at(scale) + other.mantissa_at(scale),
+            scale,
+        }
+        .normalize()
     }
+}
 
-    fn kv_is_red(kv: (&String, &Value)) -> bool {
-        match kv.1 {
-            Value::String(s) => s == ("red"),
-            _ => false,
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            return write!(f, "{}", self.mantissa);
         }
+        let sign = if self.mantissa < BigInt::ZERO {
+            "-"
+        } else {
+            ""
+        };
+        let digits = self.mantissa.magnitude().to_string();
+        let digits = format!("{:0>width$}", digits, width = self.scale as usize + 1);
+        let (int, frac) = digits.split_at(digits.len() - self.scale as usize);
+        write!(f, "{sign}{int}.{frac}")
     }
+}
 
+/// Sums every number in the document that passes all of the filters
+fn sum_numbers(v: &Value, filters: &[Filter], mode: Mode) -> Result<Decimal, String> {
+    walk_numbers(v, filters, mode, Parent::Root, 0)
+}
+
+fn walk_numbers(
+    v: &Value,
+    filters: &[Filter],
+    mode: Mode,
+    parent: Parent,
+    depth: usize,
+) -> Result<Decimal, String> {
     match v {
-        Value::Number(num) => num.as_i64().unwrap(),
-        Value::Array(arr) => arr.iter().map(walk_numbers_non_red).sum(),
-        Value::Object(obj) => {
-            if obj.into_iter().any(kv_is_red) {
-                0
-            } else {
-                obj.into_iter().map(kv_walk_numbers_non_red).sum()
+        Value::Number(num) if filters.iter().all(|f| f.counts(parent)) => {
+            let n = Decimal::parse(num)?;
+            if mode == Mode::Integer && !n.is_integer() {
+                return Err(format!("{num} is not an integer"));
             }
+            Ok(n)
+        }
+        Value::Array(arr) if filters.iter().all(|f| f.enters(v, depth)) => {
+            let children = arr.iter().map(|x| (x, Parent::Array));
+            sum_children(children, filters, mode, depth)
         }
-        _ => 0,
+        Value::Object(obj) if filters.iter().all(|f| f.enters(v, depth)) => {
+            let children = obj
+                .iter()
+                .filter(|(k, _)| filters.iter().all(|f| f.follows(k)))
+                .map(|(_, x)| (x, Parent::Object));
+            sum_children(children, filters, mode, depth)
+        }
+        _ => Ok(Decimal::zero()),
     }
 }
 
+fn sum_children<'a>(
+    mut children: impl Iterator<Item = (&'a Value, Parent)>,
+    filters: &[Filter],
+    mode: Mode,
+    depth: usize,
+) -> Result<Decimal, String> {
+    children.try_fold(Decimal::zero(), |sum, (x, parent)| {
+        Ok(sum + walk_numbers(x, filters, mode, parent, depth + 1)?)
+    })
+}
+
 const INPUT_A: &str = r#"{"e":{"a":{"e":-39,"c":119,"a":{"c":65,"a":"orange","b":"green","d":"orange"},"g":"violet","b":{"e":6,"c":{"c":"violet","a":8,"b":["red",{"a":37},"green",84,"yellow","green",[24,45,"blue","blue",56,"yellow"],"orange"]},"a":"violet","b":{"a":85},"d":[109,66,["yellow","violet",21,-30],"violet","blue",-43,{"e":"violet","c":"red","a":"blue","b":-22,"d":[71,"red",30,"violet","red",26,120],"f":["red"]},"red"]},"d":{"e":"violet","a":"blue","d":"blue","c":"blue","h":"orange","b":{"e":"red","a":{"c":115,"a":137,"b":"green"},"d":-25,"c":"blue","h":{"a":161,"b":["yellow",56,129,-31,"yellow","red","green",105,"orange",130]},"b":142,"g":194,"f":122,"i":-16},"g":173,"f":["orange","green",54,-9],"i":-23},"f":{"c":110,"a":"yellow","b":[{"a":155},156,"violet",94,"yellow"],"d":{"e":91,"a":-18,"d":"red","c":["green","orange","orange",190,"yellow",158,"blue","orange","blue",4],"h":143,"b":"orange","g":145,"f":["orange",37,"yellow",-22,{"c":30,"a":78,"b":196,"d":84},-7,["yellow"]]}}},"b":[[{"c":0,"a":108,"b":"green","d":{"e":59,"c":119,"a":104,"b":167,"d":"blue"}},[189,"blue",121,[["green","orange","orange",-17,192,"red"],{"a":"violet"},"green",{"c":42,"a":"blue","b":"red"},{"e":78,"a":"blue","d":"violet","c":-9,"h":"violet","b":115,"g":"orange","f":"violet","i":"red"}],57,"violet"],"green"],[["blue",[1,53,"orange"],{"e":["green",-12,"blue","orange","green",136,173],"a":"violet","d":-43,"c":{"e":144,"c":133,"a":"yellow","g":154,"b":"orange","d":127,"f":194},"h":{"e":52,"a":-43,"d":"orange","c":-45,"h":"orange","b":150,"g":-12,"f":91,"i":6},"b":{"e":"yellow","c":"blue","a":"violet","g":112,"b":174,"d":"violet","f":90},"g":177,"f":"blue"},"red","violet",96],"green","violet",[{"a":["red","red",46,"red"],"b":["green",193,54,"orange"]},["orange",8,1,["violet",84,"violet"],155,"yellow",151,"blue",196],"yellow","red",{"a":["green","orange","green",61,"blue",39,-2,46,"red",54]},"violet",128]]]},"a":{"e":[{"e":["yellow"],"c":93,"a":"violet","b":{"a":{"a":"yellow","b":"blue"},"b":-4},"d":"violet"},171,103,[13,"orange",[[51,"violet","yellow",{"c":85,"a":103,"b":"green"},97,{"e":"orange","a":-11,"d":62,"j":"yellow","c":"orange","h":47,"b":83,"g":119,"f":180,"i":136},{"a":177},80],{"e":{"c":"yellow","a":"orange","b":3,"d":197},"a":130,"d":"red","j":"red","c":-44,"h":-15,"b":64,"g":125,"f":82,"i":"green"}],{"e":["orange",42,["orange",197,"violet","yellow","blue",11,"yellow"],189,"yellow","blue","green","violet"],"a":{"a":149,"b":69},"d":128,"c":[["green",150,45,86,"red",-8,41,"orange","blue"]],"h":[[-4,127,"yellow","violet",124,112,196,"violet",161,40],37,0,"orange",-30,-43,[-24,"orange",142,"violet","red"],"blue",66],"b":{"c":"violet","a":["yellow",91,182,20,"orange",159,46,55,141],"b":{"c":173,"a":-40,"b":"green","d":"violet"},"d":[67,80,27,-15]},"g":"red","f":{"c":"orange","a":99,"b":"green"}},{"e":{"c":40,"a":"orange","b":"green"},"c":"green","a":-44,"b":{"e":"blue","c":56,"a":"yellow","g":62,"b":188,"d":141,"f":-21},"d":"yellow","f":{"e":"yellow","c":67,"a":33,"g":"yellow","b":"yellow","d":51,"f":195}}],["orange","violet",["red"],["green",35,[170,-30,"orange",140,"green","violet","violet",["orange","yellow","yellow",35,"blue","violet",-36,182,"yellow",141],146]],{"a":61,"b":"orange"},[31,"blue","green",65,"red","green"],"violet"],{"c":"blue","a":["blue"],"b":17},95],"c":[[48,"blue",[49],"orange",{"e":"violet","c":"green","a":"red","b":"red","d":-29,"f":["orange",20,190,97,["orange","blue",-30,"blue","green"],"blue","yellow",-47,[123,"yellow","green"],-41]},"green",{"a":170,"b":32},[{"c":"green","a":"violet","b":"red"},["yellow",36,"yellow","violet",149,{"e":"red","c":141,"a":-24,"b":"yellow","d":-13,"f":"red"},69,"orange",19,[87,"red",167,"red",77,110]],"orange","violet"],{"a":-16}],[["red"],"violet"],{"a":[["red",83,{"e":"red","c":"blue","a":"blue","b":"orange","d":"orange"},49,"green","violet"],{"e":"yellow","a":"violet","d":17,"j":"green","c":{"e":"orange","c":"green","a":"green","b":99,"d":"yellow","f":"orange"},"h":9,"b":159,"g":"yellow","f":167,"i":147},["blue","green","violet"],"yellow",["violet",197,"blue",[170,81,"yellow","orange",196],-24,99,193],-8,["red",81,-11,"green","red","blue","yellow","blue",["blue","violet",131,184,160,-1]],"green"]},"orange"],"a":[{"e":"blue","a":"violet","d":[{"a":"blue","b":46},"violet",72,35,61,161],"j":["green",78,144,[168,["red",77,38,"green","red"],"green","yellow",-8,"yellow"],190,40,"yellow",17,171],"c":{"e":"red","c":"violet","a":"violet","b":-14,"d":"red","f":167},"h":[[[83,"green",69,"red"],"green",155,133],106,1,"orange"],"b":-41,"g":{"a":27},"f":"red","i":{"e":"green","c":"green","a":182,"g":"orange","b":"orange","d":["orange"],"f":"orange"}},"yellow","green",-26],"b":{"e":[-32,-11,{"e":"red","c":"yellow","a":{"e":"green","a":"red","d":105,"c":-20,"h":85,"b":{"e":47,"a":89,"d":"green","c":"violet","h":"orange","b":"green","g":140,"f":"green"},"g":111,"f":"orange","i":"yellow"},"b":143,"d":{"e":{"e":"orange","c":129,"a":"blue","b":142,"d":"violet"},"c":"blue","a":122,"b":["violet","orange",84,"orange"],"d":"red","f":"blue"}},58,[147]],"a":{"e":"orange","c":[{"e":141,"c":114,"a":137,"g":"orange","b":61,"d":105,"f":33}],"a":["red","red",{"e":"green","c":4,"a":"violet","b":"red","d":"blue"},"yellow",["green",15,"green",-40,149,"orange",{"e":-7,"c":74,"a":"red","b":"green","d":32}],"blue","yellow",146,[191,"blue",["orange","blue",187,"blue","orange",127,"yellow",38],120,161,55,-30,"green",-10,"violet"],"orange"],"b":{"a":-32,"b":"blue"},"d":{"e":["green",27],"c":[94,"violet","red",18,166,"yellow"],"a":{"e":"green","a":95,"d":"green","j":176,"c":84,"h":"violet","b":"yellow","g":-25,"f":51,"i":119},"b":144,"d":{"c":"violet","a":"yellow","b":"red"}}},"d":{"e":{"e":140,"a":{"e":"green","c":"green","a":0,"b":68,"d":152,"f":"red"},"d":["blue",6],"c":-29,"h":"green","b":["violet",106,"violet","orange",-38,175],"g":54,"f":[177,31,"violet","yellow"],"i":185},"a":"green","d":[111,49,"yellow","blue","orange",{"e":"yellow","a":"orange","d":"violet","j":"blue","c":"red","h":88,"b":-1,"g":"red","f":"red","i":{"e":121,"a":112,"d":195,"j":103,"c":94,"h":"red","b":12,"g":8,"f":22,"i":"orange"}},64,["violet",["blue",76,"blue","red","red"],["violet","blue","orange","yellow",144],185,{"a":66,"b":"orange"},199,"green","green"],14],"c":{"e":"green","a":"orange","d":{"c":[-23,189,-11,"green","violet",178,-4,"blue",68,"violet"],"a":["orange","red"],"b":"yellow","d":"yellow"},"j":{"c":-33,"a":"blue","b":"violet"},"c":107,"h":{"e":-16,"a":174,"d":{"e":"orange","c":"green","a":-28,"b":-30,"d":73},"j":"yellow","c":"orange","h":"orange","b":["blue","violet",-4,76,"red","red"],"g":59,"f":12,"i":199},"b":{"a":"green","b":54},"g":"violet","f":174,"i":["orange",41,85,"yellow","green",25,"red",-20,156,143]},"h":"yellow","b":"red","g":90,"f":-37,"i":{"a":146,"b":"violet"}},"j":"blue","c":[1,["yellow",-11,"green",66,"red",90,"green","yellow",3,{"e":-34,"a":194,"d":"green","c":[-11,"yellow"],"h":59,"b":"yellow","g":"blue","f":162,"i":"violet"}],81,158,170],"h":"blue","b":"green","g":["violet",["blue","blue"]],"f":[-44,"yellow",[35,[122,"red","yellow",{"a":"red","b":"violet"},"blue","orange","violet"],"violet",{"e":-48,"a":6,"d":-6,"c":4,"h":"yellow","b":"blue","g":"red","f":"red"}],[{"e":-2,"a":156,"d":"red","j":69,"c":0,"h":"violet","b":"orange","g":61,"f":102,"i":["orange","blue","violet",-1,137]},{"a":183,"b":89}],{"a":167,"b":"orange"},193,95,[97,[66,154,-32,"orange",121,{"e":198,"a":"blue","d":102,"c":"red","h":"green","b":135,"g":"orange","f":-49,"i":151},97,7,"red"],195,"blue",49,"green",10]],"i":["violet",1,{"e":"orange","c":196,"a":"blue","g":"red","b":190,"d":87,"f":128},"blue",128,147]},"d":[[{"a":"green","b":43},169,-8,"orange",{"a":["violet",["red","violet","orange","yellow",-49,"violet",-25],"violet",60,33,"violet",["yellow","orange",31,144,"red","yellow",73,"orange","red","green"],["green",-31,"red"],"orange","blue"]},"violet","blue"],40,154,{"a":{"c":"violet","a":145,"b":166,"d":["blue",["blue",125,185,"yellow","red",152,89,-18,"blue",141],26,2,35,"orange",190]}},170,"violet",-31,[[151,"orange","green",["blue",114,-5,"yellow"],"blue","green","orange","yellow",62,{"c":22,"a":"green","b":"blue"}],17,[176],"violet",129,67,{"c":{"c":"red","a":140,"b":25},"a":["blue","green",163],"b":30,"d":[60,"green","red",126,[48,"green"],-26,["yellow","green",150,"red","violet","red","violet",59,-12],167,"yellow"]},"yellow","yellow"],"blue"]},"d":{"c":"green","a":52,"b":[136,{"c":{"a":[197,["orange","red",138,67,"orange",172,2,"orange"],"red"]},"a":"violet","b":{"a":{"e":172,"c":171,"a":"yellow","b":191,"d":{"e":171,"a":"red","d":"violet","c":"green","h":"yellow","b":"yellow","g":73,"f":"yellow"}}},"d":[86,-11,-5,["orange","green",64,["blue",15,"orange","yellow","violet",181,"green","blue"],"yellow","yellow",{"e":27,"c":156,"a":"blue","g":"violet","b":38,"d":51,"f":23},"orange","violet"],10]},[55,{"e":191,"c":"blue","a":"orange","b":"yellow","d":109},"blue",{"c":164,"a":[198,-9,183,{"e":14,"a":176,"d":117,"c":"violet","h":"violet","b":-44,"g":"violet","f":"red","i":"orange"},"yellow",0],"b":58,"d":33},"red",[125,23,"blue",149,[["blue",-44,22,133,"orange","yellow","yellow","violet","violet",131],"violet",-1,"red",66,"blue"],"green","red","red",[-31,"blue",["orange","orange","yellow",44,"green","yellow","green",160,"violet"],"yellow",["orange","violet","green","violet",194,"blue",-27],"green",{"e":"violet","a":"red","d":67,"c":68,"h":"blue","b":"orange","g":"orange","f":"violet","i":"violet"},"yellow"]],"violet",[59,158,{"e":"blue","c":"blue","a":"orange","g":73,"b":97,"d":"red","f":"orange"}],["red",[31,[95,72,"orange","yellow"],"blue",192,63],[197,"green",{"e":112,"a":"violet","d":"blue","c":-2,"h":"blue","b":"green","g":124,"f":"blue"},177,"green","blue",162,107]]],43,[{"e":"red","c":{"e":["green",-33,11,154,"yellow",-4],"c":"yellow","a":[7,"orange","orange","yellow",118,169,"red","blue"],"b":"green","d":114},"a":{"e":"green","a":"green","d":187,"j":{"e":106,"c":-41,"a":"violet","b":173,"d":12},"c":"red","h":"violet","b":-4,"g":{"c":"violet","a":-26,"b":87,"d":-33},"f":"green","i":-46},"b":"green","d":13,"f":"yellow"}],["blue","violet","orange","red",{"e":"green","a":["violet",140,[76,"green",94,33,"green",31,"orange",53],"violet","yellow","violet","blue",["blue",196,"orange","yellow","orange",-42,"orange",171,-47,"violet"],"green",110],"d":31,"c":"orange","h":{"e":-45,"a":"green","d":["violet"],"c":"red","h":105,"b":130,"g":-36,"f":"orange","i":{"e":"red","c":137,"a":54,"g":"blue","b":"violet","d":102,"f":178}},"b":-41,"g":{"e":6,"a":-25,"d":66,"j":"violet","c":39,"h":30,"b":63,"g":46,"f":56,"i":"blue"},"f":6,"i":"violet"},[84,175,"orange",{"a":"green"},"green",66,{"e":"red","a":{"a":178,"b":"yellow"},"d":"yellow","c":["red","red",38,"orange","blue",136,"red",137,"red"],"h":163,"b":["orange",84,"red",178],"g":65,"f":"blue"}],[98,"yellow",[46,"orange","yellow","yellow"],"blue",151,189,["yellow",{"e":"orange","a":27,"d":45,"c":48,"h":"green","b":90,"g":180,"f":-43},"yellow"],"blue"],{"a":"green"},"blue",{"c":37,"a":{"e":59,"c":["blue"],"a":"yellow","g":38,"b":"blue","d":"violet","f":"orange"},"b":"orange","d":"violet"}],{"c":75,"a":{"e":{"a":130},"a":5,"d":1,"c":72,"h":{"e":"red","a":11,"d":157,"c":97,"h":-24,"b":"red","g":111,"f":21},"b":{"e":145,"a":"red","d":"violet","j":[-11,191,-43,"blue","orange",105,158],"c":"red","h":143,"b":{"e":"orange","c":"yellow","a":-31,"g":177,"b":"violet","d":"blue","f":"green"},"g":"green","f":48,"i":{"e":18,"a":142,"d":"yellow","c":116,"h":"violet","b":135,"g":37,"f":36}},"g":-26,"f":[186,192,"orange",117,-9,"violet",-19,55,"green",167],"i":"green"},"b":"yellow","d":{"a":138}}]},"c":"blue","h":[[19],"orange",[{"e":["yellow",68,28,29,{"e":"red","a":"violet","d":"green","c":143,"h":"red","b":"orange","g":44,"f":123,"i":"orange"},38,28,65,{"e":-26,"c":["red",132,"red",124,"yellow",115],"a":170,"g":"yellow","b":"green","d":-18,"f":{"c":1,"a":"red","b":-1}}],"a":"orange","d":129,"c":33,"h":"violet","b":"orange","g":"green","f":-24},["violet",-22],[64,-20,{"e":46,"a":76,"d":97,"c":-21,"h":98,"b":"violet","g":{"e":-7,"c":"violet","a":190,"b":"violet","d":138,"f":"violet"},"f":[178,"blue","blue"],"i":"red"},{"e":"orange","c":"red","a":{"e":"orange","a":["green"],"d":[19,"red"],"c":-32,"h":-15,"b":"yellow","g":116,"f":"blue","i":"orange"},"b":96,"d":"green","f":176},[[104,99,"yellow",-13,"red",{"c":"blue","a":"red","b":46,"d":60},-13,9],-22,-26,133,["green",["red",188,"green","green"],166,"yellow",{"e":"yellow","c":197,"a":65,"b":83,"d":-19,"f":"yellow"},[157,"violet","blue","yellow",-30,"violet",-3],21,{"a":-15}],43,["orange","blue",88,"yellow",103,31],"orange",-24]],{"c":"violet","a":{"a":26,"b":"yellow"},"b":["violet","violet",{"e":102,"a":"red","d":178,"j":187,"c":"orange","h":"violet","b":72,"g":-37,"f":"violet","i":"orange"},44,114,"yellow",85]}],{"e":150,"c":"green","a":{"e":{"e":{"e":{"e":"orange","a":48,"d":41,"j":"orange","c":"orange","h":30,"b":"yellow","g":41,"f":-40,"i":8},"c":199,"a":32,"b":"yellow","d":-28},"a":46,"d":[50,"red","violet",63,"red",56,-18,"orange","violet","red"],"c":"blue","h":{"e":"green","c":172,"a":"green","b":-30,"d":22,"f":"yellow"},"b":"orange","g":65,"f":"yellow","i":174},"a":"green","d":[196,{"e":{"e":176,"a":"violet","d":"orange","j":-37,"c":19,"h":31,"b":155,"g":"red","f":106,"i":"green"},"c":"red","a":64,"b":"orange","d":179,"f":8},"yellow",103,"violet",{"e":142,"a":111,"d":"yellow","c":"violet","h":"red","b":148,"g":29,"f":179,"i":"green"},-26],"c":-19,"h":["red",134,"green","green",105],"b":"orange","g":126,"f":76,"i":158},"b":55,"d":"green"},[[[61],[["orange","red"],[151,"yellow",127,"yellow",185,"yellow",{"e":105,"c":"yellow","a":198,"b":"orange","d":"blue","f":89},140,{"e":"violet","a":-15,"d":169,"j":3,"c":"yellow","h":74,"b":-41,"g":29,"f":112,"i":18}]],101,{"e":[45,"green","yellow","blue","violet",["violet","yellow",159,0,"orange","yellow",100,"green","blue",49],[5,-37],"blue","orange"],"c":88,"a":"blue","b":-32,"d":23},77,{"e":["yellow",119,197,["orange",-28,"yellow",179,130,74,-10,115,"violet",79],"orange",63,-15,17,"blue","violet"],"c":{"e":164,"a":83,"d":"yellow","c":119,"h":"yellow","b":148,"g":-22,"f":[-17,17,"violet","green","red"],"i":67},"a":{"e":54,"a":147,"d":"yellow","c":86,"h":113,"b":"yellow","g":77,"f":101,"i":"blue"},"g":"orange","b":[193,"orange","orange","red",39,44,43,-29],"d":"violet","f":191}],"orange",["red","violet","yellow",["red",54,{"e":{"a":41,"b":"violet"},"c":"red","a":{"e":"red","a":"yellow","d":"green","j":-33,"c":96,"h":137,"b":"yellow","g":30,"f":"green","i":"blue"},"b":181,"d":"violet","f":"green"},49,"yellow"],"orange","yellow",{"e":"orange","c":[46,"orange",["blue","green","blue","yellow","yellow","violet","orange","orange",1],"yellow",155,194,"yellow",149],"a":"green","b":-3,"d":153,"f":[-21,-26,-25,"blue","red",108,169,["green",100,43],51,-9]}],71,[[[{"a":"violet","b":27},148,109,["blue",60,47,"violet","yellow",-47,"violet"],"red",{"e":97,"c":-21,"a":"yellow","b":"green","d":126},"yellow",85,89],{"a":["red","orange","violet","blue","blue","blue",128,"blue"]},{"e":-48,"c":{"c":90,"a":"orange","b":"yellow"},"a":"yellow","b":"red","d":172},"yellow",{"a":"orange"},[{"e":"yellow","a":40,"d":-9,"j":"violet","c":153,"h":79,"b":"violet","g":178,"f":2,"i":"yellow"},"green",-29],"red",-9,[9,{"a":39,"b":"green"},5,"violet",26,{"e":167,"c":"blue","a":"yellow","b":90,"d":33},"green",88,12,"blue"]],"red",{"c":{"e":"yellow","a":-44,"d":62,"j":-19,"c":96,"h":"green","b":-12,"g":"green","f":"red","i":134},"a":22,"b":24,"d":33},"red","violet",{"e":181,"c":64,"a":"violet","g":"green","b":"yellow","d":"violet","f":101},"green",[132,93,[-6,[-3,28,"red"],-21,"red","violet",{"a":180},"red","blue",-15],{"e":171,"a":{"e":"green","c":49,"a":"orange","g":22,"b":"violet","d":"orange","f":"orange"},"d":"red","c":113,"h":"green","b":[30,"blue"],"g":{"a":139,"b":47},"f":"red","i":"red"},"violet",158,"green",5,-1],19],"red"],{"e":{"c":["orange",93,162,"green","violet",["green",178],{"a":"green","b":{"e":-12,"a":-16,"d":144,"c":"red","h":"violet","b":43,"g":"green","f":-42}}],"a":"blue","b":"blue","d":{"e":["orange","red",{"c":-19,"a":"green","b":93},"yellow","green","orange","orange","red","green"],"a":"green","d":{"e":"yellow","a":2,"d":"violet","j":"green","c":"blue","h":19,"b":"violet","g":"blue","f":[157,"green",109,59,"red",74,"red","blue","green"],"i":"orange"},"j":"yellow","c":166,"h":"yellow","b":"yellow","g":["violet",138,["violet",141,"green"]],"f":28,"i":{"e":52,"c":"yellow","a":"green","b":5,"d":{"e":153,"a":"yellow","d":191,"j":"green","c":"green","h":124,"b":"green","g":181,"f":134,"i":"yellow"},"f":193}}},"c":"violet","a":{"a":{"a":"green"},"b":[132]},"b":[{"e":11,"a":"green","d":{"e":{"e":119,"a":"violet","d":"red","c":"red","h":"violet","b":-6,"g":"blue","f":"orange","i":"orange"},"a":183,"d":[-36,"yellow"],"c":"red","h":71,"b":"yellow","g":2,"f":"orange"},"c":"green","h":"yellow","b":29,"g":"green","f":"blue","i":{"e":"yellow","a":-24,"d":[55,125,193,70,60,190,199],"c":"green","h":[49,"yellow","yellow",74,"red",163],"b":198,"g":50,"f":"blue","i":70}},"orange"],"d":"blue"},{"c":{"e":-20,"a":8,"d":["orange",157,152,"green",46,"green",7,89,"violet",[-22,-49,81,127]],"c":35,"h":["blue","yellow","orange",94,"orange","yellow"],"b":"red","g":{"a":"green","b":["yellow","orange",198]},"f":"violet","i":17},"a":-48,"b":"blue"}],"b":[[149,[{"e":"blue","c":-30,"a":"violet","g":"violet","b":"yellow","d":178,"f":-4}],"orange",131,"yellow",{"a":"red","b":"violet"},"blue"],[11],{"e":[35,118,{"e":39,"a":"yellow","d":[119,"orange",120,-43],"j":"violet","c":"orange","h":"blue","b":-32,"g":[{"e":"green","c":106,"a":144,"b":147,"d":"green"},"violet","orange"],"f":116,"i":"orange"},112,"yellow"],"a":{"e":19,"a":"orange","d":61,"c":"red","h":"blue","b":164,"g":{"c":"red","a":-10,"b":{"e":{"e":"green","c":82,"a":103,"g":67,"b":153,"d":"violet","f":22},"c":-22,"a":101,"b":71,"d":{"a":152,"b":"green"},"f":"orange"}},"f":157,"i":{"e":"green","a":"orange","d":-8,"j":[159,73,182,"red","green"],"c":13,"h":"blue","b":"yellow","g":186,"f":"orange","i":81}},"d":13,"c":[186,["yellow",["violet","violet"],"green",-28],54,["blue",[119,"red",119,91,181],117],-15,190,{"c":"red","a":13,"b":[{"c":"green","a":70,"b":8,"d":175},"orange","green","yellow","green"]},"blue",-43],"h":{"e":[{"a":"green"},88,"red","violet",10],"a":{"a":"orange","b":[62,"yellow","green"]},"d":151,"c":"red","h":"orange","b":126,"g":{"a":"orange"},"f":"orange","i":46},"b":[[-2,"violet","violet","red",{"a":192},"green",122],["orange","red",{"c":62,"a":52,"b":-45},{"e":{"a":"violet","b":-45},"a":"red","d":"orange","c":"yellow","h":{"e":67,"c":-27,"a":116,"b":"violet","d":"green","f":-18},"b":"yellow","g":"blue","f":"blue"},27,{"c":37,"a":-39,"b":"blue"}],107,"yellow",["blue","red",143],"blue",{"e":"orange","a":["yellow",["blue",8,149,141,"red",-28,"red"],18],"d":29,"c":"violet","h":-21,"b":[{"e":66,"c":"green","a":"blue","b":-29,"d":"orange","f":"violet"},"blue",-21],"g":"green","f":112},73],"g":["red",{"a":{"e":93,"a":13,"d":"violet","c":175,"h":158,"b":9,"g":194,"f":-10},"b":"blue"}],"f":130}],"g":[["red","orange","orange",172,154,{"e":{"a":"blue","b":{"a":"yellow","b":53}},"c":-11,"a":"orange","g":"yellow","b":{"e":{"e":94,"a":123,"d":184,"j":-4,"c":193,"h":152,"b":"blue","g":"red","f":101,"i":178},"c":"blue","a":178,"b":154,"d":[103,109,190,"yellow",29,"red","orange","yellow",79,"green"],"f":"orange"},"d":{"e":25,"a":{"a":"blue"},"d":"green","c":"orange","h":{"e":144,"c":["red","blue","violet",15,"green",109,72],"a":"yellow","b":"orange","d":"yellow"},"b":151,"g":[141,"orange",134,"blue","blue",4,21,"blue","green"],"f":96},"f":"blue"},"blue"],80,186,[[194,"violet",70,"green"],{"a":"orange","b":"green"},[{"e":"orange","a":179,"d":{"e":164,"c":-14,"a":"blue","g":"yellow","b":"violet","d":76,"f":-33},"c":"green","h":"violet","b":"orange","g":"blue","f":"orange","i":"green"},"blue"]],"orange","yellow",66],"f":{"a":["violet"],"b":87},"i":{"e":{"c":[{"e":"red","a":19,"d":100,"j":"red","c":"red","h":"red","b":"yellow","g":-41,"f":10,"i":"blue"},42,92,"violet","red",[149,"green",91,"blue"],-33,["green",73,129],110,{"e":168,"c":153,"a":-30,"b":"yellow","d":[192,{"c":"blue","a":"blue","b":-16,"d":-18},{"e":"red","c":"yellow","a":"violet","b":31,"d":"green"},"blue"],"f":{"c":9,"a":"yellow","b":-16,"d":128}}],"a":[[["green","violet",136,59,"orange",173,116,113,"yellow"],"green",{"e":"blue","c":"green","a":"blue","g":"red","b":98,"d":-25,"f":21},"orange",184],4,"yellow","red",-24,{"c":"orange","a":15,"b":{"e":109,"c":179,"a":61,"b":"orange","d":190,"f":9}}],"b":[[92,148,{"e":"blue","a":0,"d":108,"c":197,"h":"red","b":"orange","g":-22,"f":105,"i":"blue"},"blue"],-7,149]},"a":{"e":{"a":{"e":"green","a":["violet",172],"d":-10,"c":42,"h":"blue","b":80,"g":{"e":"red","c":"orange","a":"yellow","g":87,"b":{"e":31,"c":129,"a":"orange","b":43,"d":"blue"},"d":"blue","f":81},"f":"violet"},"b":"orange"},"a":182,"d":{"c":{"a":154},"a":"blue","b":{"e":31,"a":["yellow","blue","red",{"a":"orange"},"red",62,39,"red",["green","orange","yellow",47,"orange",55,"blue"]],"d":[72,187,"red","orange",59,"yellow","violet","green"],"j":"orange","c":"yellow","h":185,"b":{"e":"blue","c":"violet","a":["orange",7,180,150,46,"yellow",176,"orange"],"b":148,"d":"blue"},"g":"blue","f":"yellow","i":102}},"c":"red","h":-9,"b":14,"g":{"a":"green","b":{"c":-18,"a":81,"b":104}},"f":[[{"e":-33,"c":"green","a":"orange","b":"blue","d":"blue"},"yellow",141,[42,197],[-12,61,{"e":"violet","a":"violet","d":"green","c":-21,"h":-5,"b":"orange","g":39,"f":"green"},"blue"],31,[[101,"blue",-14,"red",88],58,["red","blue","violet",34],-14,"yellow",98,106,91,131],"yellow",[151,"red","green",{"e":"violet","c":"green","a":49,"g":155,"b":96,"d":"blue","f":"orange"},-18,184,{"c":"blue","a":1,"b":162},{"e":115,"a":94,"d":97,"j":-34,"c":"blue","h":115,"b":"red","g":"orange","f":149,"i":105}],38],{"c":{"e":"blue","c":90,"a":"yellow","b":142,"d":"violet"},"a":-38,"b":"violet"}],"i":189},"d":"orange","c":[[103,8,"green",13,23,"violet",55],[{"e":79,"c":74,"a":{"e":"violet","a":{"c":"red","a":"violet","b":168},"d":142,"j":"blue","c":173,"h":"yellow","b":"green","g":"blue","f":"blue","i":"blue"},"g":[{"c":"violet","a":82,"b":90},-24,{"a":"orange"},"yellow",[126,53,153,6],52,137,"violet",181],"b":105,"d":166,"f":{"e":"blue","c":-35,"a":"blue","b":188,"d":-14,"f":63}},{"e":95,"a":"green","d":"yellow","j":"red","c":81,"h":107,"b":-46,"g":162,"f":"green","i":"red"},[{"e":"violet","a":150,"d":126,"c":10,"h":{"e":"green","a":"orange","d":19,"c":"green","h":"green","b":79,"g":"red","f":"yellow","i":"blue"},"b":{"a":25,"b":147},"g":180,"f":126},{"e":-48,"a":192,"d":-45,"c":25,"h":"green","b":{"c":165,"a":"orange","b":"red","d":"blue"},"g":"green","f":-24},95,{"e":"blue","a":"violet","d":"yellow","j":"blue","c":44,"h":["blue","green","red",142,"red"],"b":[-43,"violet","green",53],"g":33,"f":"orange","i":196},"orange","green",43,[113,"violet","orange",129,{"c":6,"a":"violet","b":"green","d":"green"},20]],"green"]],"h":{"a":-11},"b":{"e":"violet","c":174,"a":"violet","g":[{"e":105,"a":{"c":-5,"a":177,"b":63},"d":-41,"c":80,"h":[110,109,113,"blue"],"b":-28,"g":"red","f":129},["green",[108],{"a":4},[182,96,29,[181,14,"yellow","violet"],13,{"e":132,"a":115,"d":"red","c":"violet","h":"violet","b":"violet","g":"green","f":"green"},{"e":"orange","a":"yellow","d":"blue","j":"red","c":137,"h":"violet","b":"green","g":-22,"f":"yellow","i":-12},"green","yellow",57],-44,{"a":88,"b":-3},22,{"c":"red","a":"violet","b":-21}],[[14,"orange"]],-36,128,"yellow",[-47,[-7,36,177],"blue","blue",["yellow","violet",-23,"violet",-40,"orange",{"e":129,"a":21,"d":51,"j":"violet","c":"red","h":15,"b":174,"g":191,"f":101,"i":105}],23,[-47,[133,66,"violet"],177,"violet","yellow","green",159,"yellow"]],{"e":[{"a":"orange"},"green",69,"orange",43,"violet","violet",192,140,"green"],"a":62,"d":"violet","j":{"c":"violet","a":-25,"b":"violet"},"c":120,"h":"red","b":{"a":13},"g":"green","f":124,"i":33},[[19,176,174,"orange","violet","violet",105,128,"red"],{"a":10,"b":"red"},["orange",37,187,"green",176],"blue","red",[153,"yellow","violet",137,"orange","blue",[70,"red",174,"blue","green","yellow",99,"red"],"violet","violet","red"]]],"b":[{"c":133,"a":"orange","b":98,"d":{"c":"red","a":-9,"b":103}},"blue",[155,"yellow"],[["yellow",-8,{"e":"blue","c":59,"a":71,"g":"orange","b":88,"d":"orange","f":17},"blue",-37],{"e":82,"c":"violet","a":99,"b":81,"d":"yellow","f":161},"blue",{"c":-7,"a":154,"b":"violet"},-9,-13,53,{"e":"violet","a":"yellow","d":-26,"c":179,"h":"green","b":"red","g":-24,"f":133,"i":-36}]],"d":{"e":[9,[49],28,"red",{"c":23,"a":157,"b":{"c":"violet","a":"green","b":-11,"d":"green"},"d":139},"orange","green",93,44,[-19]],"a":-6,"d":7,"c":{"e":{"c":"violet","a":-21,"b":12},"c":["green",[-23,-40,157],"red",["orange","blue"],"violet","red",85,"violet","yellow",150],"a":162,"g":"yellow","b":"green","d":["green","yellow",181,"green",-2,{"e":"red","a":"yellow","d":129,"c":-34,"h":129,"b":152,"g":"yellow","f":80,"i":"red"},"green","red",-9,"red"],"f":6},"h":{"e":136,"a":"violet","d":["violet",-15,129,"green",5,"green",[-28,156,141,"blue",22,"green",34],"green"],"c":149,"h":["violet"],"b":149,"g":"green","f":149},"b":{"e":123,"a":"red","d":{"e":0,"c":"green","a":"violet","b":"yellow","d":"red","f":"green"},"c":{"e":{"e":"yellow","c":"red","a":"red","b":"violet","d":"yellow"},"a":"yellow","d":{"e":-39,"a":-11,"d":63,"c":179,"h":4,"b":44,"g":"orange","f":"violet","i":"yellow"},"c":-43,"h":"green","b":"violet","g":"blue","f":"yellow","i":124},"h":48,"b":129,"g":["orange",147,174,"blue","green",115],"f":172,"i":-36},"g":"red","f":30,"i":"violet"},"f":"red"},"g":{"e":{"e":[[126,"orange",196,"orange","red",{"e":23,"a":"yellow","d":128,"c":12,"h":-49,"b":"green","g":"yellow","f":41,"i":45},"red","violet"]],"c":[["red",103,{"e":69,"a":"violet","d":"yellow","c":"green","h":"red","b":133,"g":25,"f":"violet"}],88,"green","red",-29,"red"],"a":37,"b":"green","d":{"e":"orange","c":{"e":{"e":109,"c":144,"a":"yellow","b":70,"d":83},"c":-3,"a":"green","b":"yellow","d":146},"a":86,"b":-1,"d":{"a":68}}},"c":{"c":[155,18,"blue",-16,"orange",-36,49,"red",["yellow",136,140,-10,11,"violet","red",134,156,"violet"]],"a":64,"b":{"e":"violet","c":193,"a":101,"b":["green"],"d":182,"f":86},"d":[198]},"a":"yellow","g":"violet","b":1,"d":-27,"f":"orange"},"f":{"e":20,"c":{"e":155,"c":{"c":[181,{"e":-32,"a":"orange","d":"orange","j":"yellow","c":66,"h":-39,"b":"violet","g":"violet","f":"red","i":23},4,"blue",70,"violet","blue",141,{"a":"yellow","b":184},"violet"],"a":160,"b":158,"d":197},"a":138,"g":[48],"b":26,"d":11,"f":{"c":"yellow","a":179,"b":"red","d":90}},"a":148,"g":"yellow","b":[[{"a":["yellow"]},"yellow",19,"green",39],{"c":{"a":"blue"},"a":{"e":41,"c":191,"a":173,"b":"green","d":-14,"f":19},"b":{"a":["orange",4,48],"b":193},"d":"green"},["yellow",{"e":"orange","a":"orange","d":"blue","c":-39,"h":28,"b":"yellow","g":"red","f":"orange","i":[116,"red",173,76,24,-1,"green",101,-10]},70,{"c":"violet","a":44,"b":"violet","d":36},128,{"c":"yellow","a":"red","b":["yellow",-10]},-10,6]],"d":"violet","f":{"e":"violet","c":5,"a":"violet","g":173,"b":100,"d":["violet",194,{"e":["blue",181,"violet","yellow","blue",-7,137,43,112],"c":-19,"a":120,"b":"green","d":165},-1,195,"green",104],"f":128}}}}"#;
 
 #[cfg(test)]
 mod tests {
-    use crate::year_2015::day_12::{part_a, part_b};
+    use crate::json_stream;
+    use crate::year_2015::day_12::{Filter, INPUT_A, Mode, part_a, part_b, sum_numbers};
+    use serde_json::Value;
+    use std::io::Read;
 
     #[test]
     fn test_a() {
-        let answer = part_a();
-        assert_eq!(119433, answer);
+        let answer = part_a(Mode::Integer).to_i64();
+        assert_eq!(Some(119433), answer);
+        assert_eq!(part_a(Mode::Decimal).to_i64(), answer);
     }
 
     #[test]
     fn test_b() {
-        let answer = part_b();
-        assert_eq!(68466, answer);
+        let answer = part_b(Mode::Integer).to_i64();
+        assert_eq!(Some(68466), answer);
+        assert_eq!(part_b(Mode::Decimal).to_i64(), answer);
+    }
+
+    fn sum(json: &str, filters: &str) -> i64 {
+        let v: Value = serde_json::from_str(json).unwrap();
+        let filters = Filter::parse(filters).unwrap();
+        sum_numbers(&v, &filters, Mode::Integer)
+            .unwrap()
+            .to_i64()
+            .unwrap()
+    }
+
+    fn sum_exact(json: &str, mode: Mode) -> Result<String, String> {
+        let v: Value = serde_json::from_str(json).unwrap();
+        sum_numbers(&v, &[], mode).map(|sum| sum.to_string())
+    }
+
+    #[test]
+    fn test_filters() {
+        assert_eq!(sum(r#"[1,{"c":"red","b":2},3]"#, ""), 6);
+        assert_eq!(
+            sum(r#"[1,{"c":"red","b":2},3]"#, r#"skip-objects-with "red""#),
+            4
+        );
+        assert_eq!(sum(r#"[1,"red",5]"#, r#"skip-objects-with "red""#), 6);
+        assert_eq!(sum(r#"{"a":1,"b":[2,{"c":3}]}"#, "only-arrays"), 2);
+        assert_eq!(sum(r#"{"a":1,"b":[2,{"c":3}]}"#, "keys ^[ab]$"), 3);
+        assert_eq!(sum(r#"[1,[2,[3,[4]]]]"#, "max-depth 2"), 3);
+        assert_eq!(sum(r#"[1,{"a":[2,3]},{"b":4}]"#, "only-arrays; keys a"), 6);
+    }
+
+    #[test]
+    fn test_filter_errors() {
+        assert!(Filter::parse("skip-objects-with red").is_err());
+        assert!(Filter::parse("keys (").is_err());
+        assert!(Filter::parse("max-depth -1").is_err());
+        assert!(Filter::parse("only-objects").is_err());
+    }
+
+    #[test]
+    fn test_numbers() {
+        // Fractions are summed without the rounding f64 would give
+        assert_eq!(
+            sum_exact("[0.1, 0.2]", Mode::Decimal),
+            Ok(String::from("0.3"))
+        );
+        assert_eq!(
+            sum_exact(r#"{"a": -0.25, "b": [1e-3, 2.5E2]}"#, Mode::Decimal),
+            Ok(String::from("249.751"))
+        );
+        assert_eq!(
+            sum_exact("[0.5, 0.5]", Mode::Decimal),
+            Ok(String::from("1"))
+        );
+
+        // Integers written with a fraction or exponent are still integers
+        assert_eq!(
+            sum_exact("[2.0, 1e3]", Mode::Integer),
+            Ok(String::from("1002"))
+        );
+        assert_eq!(
+            sum_exact("[0e20000, 0.0e-20000, 7]", Mode::Integer),
+            Ok(String::from("7"))
+        );
+    }
+
+    #[cfg(feature = "exact-json")]
+    #[test]
+    fn test_exact_numbers() {
+        // Beyond i64 and u64, where as_i64 would have failed
+        assert_eq!(
+            sum_exact("[9223372036854775807, 1]", Mode::Integer),
+            Ok(String::from("9223372036854775808"))
+        );
+        assert_eq!(
+            sum_exact(
+                "[18446744073709551615, 18446744073709551615, -1]",
+                Mode::Integer
+            ),
+            Ok(String::from("36893488147419103229"))
+        );
+        assert_eq!(
+            sum_exact("[123456789012345678901234567890]", Mode::Integer),
+            Ok(String::from("123456789012345678901234567890"))
+        );
+        assert_eq!(
+            sum_exact("[2.0, 1e3, 15E-1, 0.5]", Mode::Integer),
+            Err(String::from("15E-1 is not an integer"))
+        );
+        assert!(sum_exact("[1e999999999]", Mode::Decimal).is_err());
+    }
+
+    #[test]
+    fn test_streaming() {
+        // The streaming sum must agree with walking the tree, even over a document of a few
+        // megabytes that is never held in memory as a whole
+        let copies = 100;
+        let reader = || {
+            (0..copies)
+                .fold(Box::new("[".as_bytes()) as Box<dyn Read>, |r, i| {
+                    let separator = if i == 0 { "" } else { "," };
+                    Box::new(r.chain(separator.as_bytes()).chain(INPUT_A.as_bytes()))
+                })
+                .chain("]".as_bytes())
+        };
+
+        let sum_a = json_stream::sum_numbers(reader(), None).unwrap();
+        let sum_b = json_stream::sum_numbers(reader(), Some("red")).unwrap();
+        assert_eq!(
+            Some(sum_a),
+            part_a(Mode::Integer).to_i64().map(|n| n * copies)
+        );
+        assert_eq!(
+            Some(sum_b),
+            part_b(Mode::Integer).to_i64().map(|n| n * copies)
+        );
     }
 }
@@ -57,16 +57,22 @@
 ///
 /// Your puzzle answer was 601.
 use crate::algorithm::heap_permutations;
+use crate::line_parser::line_parser;
 use indoc::indoc;
-use regex::Regex;
 use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::time::SystemTime;
 
 pub fn run() {
     println!("--- Day 13: Knights of the Dinner Table --- ");
 
     let now = SystemTime::now();
-    let answer_a = part_a();
+    let guests = all_knights_and_relationships(_INPUT_A);
+    let duration = now.elapsed().expect("Elapsed failed");
+    println!("Parsed the guest list\n in {}ms", duration.as_millis());
+
+    let now = SystemTime::now();
+    let answer_a = part_a(&guests);
     let duration = now.elapsed().expect("Elapsed failed");
     println!(
         "What is the total change in happiness for the optimal seating arrangement of the actual guest list?\n {}\n in {}ms",
@@ -75,7 +81,7 @@ pub fn run() {
     );
 
     let now = SystemTime::now();
-    let answer_b = part_b();
+    let answer_b = part_b(&guests);
     let duration = now.elapsed().expect("Elapsed failed");
     println!(
         "What is the total change in happiness for the optimal seating arrangement that actually includes yourself?\n {}\n in {}ms",
@@ -84,41 +90,46 @@ pub fn run() {
     );
 }
 
-fn part_a() -> i32 {
-    let (all_knights, relationships) = all_knights_and_relationships(_INPUT_A);
+fn part_a(guests: &Guests) -> i32 {
+    let seatings = heap_permutations(&guests.knights);
 
-    let seatings = heap_permutations(&all_knights);
-
-    happiest(seatings, relationships)
+    happiest(seatings, &guests.relationships)
 }
 
-fn part_b() -> i32 {
-    let (mut all_knights, relationships) = all_knights_and_relationships(_INPUT_A);
+fn part_b(guests: &Guests) -> i32 {
+    let mut all_knights = guests.knights.clone();
     all_knights.insert(String::from("Me"));
 
     let seatings = heap_permutations(&all_knights);
 
-    happiest(seatings, relationships)
+    happiest(seatings, &guests.relationships)
+}
+
+/// Everyone at the table, and how much each of them would gain or lose by sitting next to another
+struct Guests {
+    knights: HashSet<String>,
+    relationships: HashMap<(String, String), i32>,
 }
 
-fn all_knights_and_relationships(input: &str) -> (HashSet<String>, HashMap<(String, String), i32>) {
-    let kr = KnightRegex::init();
+fn all_knights_and_relationships(input: &str) -> Guests {
     let mut all_knights: HashSet<String> = HashSet::new();
 
     let mut relationships: HashMap<(String, String), i32> = HashMap::new();
-    for line in input.lines() {
-        if let Some(r) = kr.parse_happiness(line) {
-            all_knights.insert(r.knight_a.clone());
-            relationships.insert((r.knight_a.clone(), r.knight_b.clone()), r.happiness);
-        }
+    let lines = KnightRelationship::parser().parse_lines(input);
+    for r in lines.expect("Invalid input") {
+        all_knights.insert(r.knight_a.clone());
+        relationships.insert((r.knight_a.clone(), r.knight_b.clone()), r.happiness());
+    }
+    Guests {
+        knights: all_knights,
+        relationships,
     }
-    (all_knights, relationships)
 }
 
-fn happiest(seatings: Vec<Vec<&str>>, relationships: HashMap<(String, String), i32>) -> i32 {
+fn happiest(seatings: Vec<Vec<&str>>, relationships: &HashMap<(String, String), i32>) -> i32 {
     let mut answer = i32::MIN;
     for seating in seatings {
-        let x = calculate_happiness(&seating, &relationships);
+        let x = calculate_happiness(&seating, relationships);
         answer = i32::max(answer, x);
     }
     answer
@@ -149,47 +160,42 @@ fn calculate_happiness(seating: &Vec<&str>, relationships: &HashMap<(String, Str
     total
 }
 
-struct KnightRelationship {
-    knight_a: String,
-    knight_b: String,
-    happiness: i32,
+line_parser! {
+    struct KnightRelationship = INPUT_REGEX {
+        knight_a: String,
+        change: Change,
+        units: i32,
+        knight_b: String,
+    }
 }
 
-struct KnightRegex {
-    happiness: Regex,
+enum Change {
+    Gain,
+    Lose,
 }
 
-impl KnightRegex {
-    fn init() -> KnightRegex {
-        KnightRegex {
-            happiness: Regex::new(INPUT_REGEX).unwrap(),
+impl KnightRelationship {
+    fn happiness(&self) -> i32 {
+        match self.change {
+            Change::Gain => self.units,
+            Change::Lose => -self.units,
         }
     }
+}
 
-    fn parse_happiness(&self, s: &str) -> Option<KnightRelationship> {
-        self.happiness.captures(s).map(|c| {
-            let a = c.name("a").unwrap().as_str();
-            let b = c.name("b").unwrap().as_str();
-            let gl = c.name("gl").unwrap().as_str();
-            let n = c.name("n").unwrap().as_str();
+impl FromStr for Change {
+    type Err = String;
 
-            let abs_happiness = n.parse::<i32>().unwrap();
-            let happiness = match gl {
-                "gain" => abs_happiness,
-                _ => -abs_happiness,
-            };
-            let knight_a = String::from(a);
-            let knight_b = String::from(b);
-            KnightRelationship {
-                knight_a,
-                knight_b,
-                happiness,
-            }
-        })
+    fn from_str(s: &str) -> Result<Change, String> {
+        match s {
+            "gain" => Ok(Change::Gain),
+            "lose" => Ok(Change::Lose),
+            _ => Err(String::from("Expected gain or lose")),
+        }
     }
 }
 
-const INPUT_REGEX: &str = "^(?<a>[a-zA-Z]+) would (?<gl>gain|lose) (?<n>[0-9]+) happiness units by sitting next to (?<b>[a-zA-Z]+)\\.$";
+const INPUT_REGEX: &str = "^(?<knight_a>[a-zA-Z]+) would (?<change>gain|lose) (?<units>[0-9]+) happiness units by sitting next to (?<knight_b>[a-zA-Z]+)\\.$";
 
 const _INPUT_SAMPLE: &str = indoc! {r#"
 Alice would gain 54 happiness units by sitting next to Bob.
@@ -265,17 +271,17 @@ Mallory would lose 16 happiness units by sitting next to George."#};
 
 #[cfg(test)]
 mod tests {
-    use crate::year_2015::day_13::{part_a, part_b};
+    use crate::year_2015::day_13::{_INPUT_A, all_knights_and_relationships, part_a, part_b};
 
     #[test]
     fn test_a() {
-        let answer = part_a();
+        let answer = part_a(&all_knights_and_relationships(_INPUT_A));
         assert_eq!(618, answer);
     }
 
     #[test]
     fn test_b() {
-        let answer = part_b();
+        let answer = part_b(&all_knights_and_relationships(_INPUT_A));
         assert_eq!(601, answer);
     }
 }
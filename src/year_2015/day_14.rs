@@ -45,15 +45,26 @@
 /// how many points does the winning reindeer have?
 ///
 /// Your puzzle answer was 1084.
+use crate::line_parser::line_parser;
 use indoc::indoc;
-use regex::Regex;
+use std::borrow::Cow;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::iter;
+use std::path::Path;
 use std::time::SystemTime;
 
 pub fn run() {
     println!("--- Day 14: Reindeer Olympics --- ");
 
     let now = SystemTime::now();
-    let answer_a = part_a();
+    let racers = parse_input(INPUT_A);
+    let duration = now.elapsed().expect("Elapsed failed");
+    println!("Parsed the reindeer\n in {}ns", duration.as_nanos());
+
+    let now = SystemTime::now();
+    let answer_a = part_a(&racers);
     let duration = now.elapsed().expect("Elapsed failed");
     println!(
         "Given the descriptions of each reindeer (in your puzzle input), after exactly 2503 seconds, what distance has the winning reindeer traveled?\n {}\n in {}ns",
@@ -62,148 +73,437 @@ pub fn run() {
     );
 
     let now = SystemTime::now();
-    let answer_b = part_b();
+    let answer_b = part_b(&racers);
     let duration = now.elapsed().expect("Elapsed failed");
     println!(
         "Again given the descriptions of each reindeer (in your puzzle input), after exactly 2503 seconds, how many points does the winning reindeer have?\n {}\n in {}ns",
         answer_b,
         duration.as_nanos()
     );
+
+    // The timeline has a row for every reindeer every second, so it is only written on request
+    if let Ok(path) = env::var(TIMELINE_VAR) {
+        let events_only = env::var(TIMELINE_EVENTS_VAR).is_ok();
+        match export_timeline(Path::new(&path), &racers, events_only) {
+            Ok(()) => println!("Wrote race timeline to {}", path),
+            Err(e) => println!("Failed to write race timeline to {}: {}", path, e),
+        }
+    }
+}
+
+fn part_a(racers: &[Reindeer]) -> u64 {
+    distance_race(racers, RACE_SECONDS)
 }
 
-fn part_a() -> u32 {
-    let rr = RaceRegex::init();
+fn part_b(racers: &[Reindeer]) -> u64 {
+    let points = points_race(racers, RACE_SECONDS);
+    points.into_iter().max().unwrap_or(0)
+}
+
+fn parse_input(input: &str) -> Vec<Reindeer> {
+    Reindeer::parser()
+        .parse_lines(input)
+        .expect("Invalid input")
+}
+
+/// Distance of the winning reindeer after the given number of seconds
+fn distance_race(racers: &[Reindeer], seconds: u64) -> u64 {
+    racers
+        .iter()
+        .map(|r| r.distance_at(seconds))
+        .max()
+        .unwrap_or(0)
+}
 
-    let mut racers: Vec<Reindeer> = Vec::new();
-    for line in INPUT_A.lines() {
-        if let Some(rd) = rr.parse(line) {
-            racers.push(rd);
+/// Points of each reindeer after the given number of seconds
+fn points_race(racers: &[Reindeer], seconds: u64) -> Vec<u64> {
+    let mut points = vec![0; racers.len()];
+    for stretch in race(racers, seconds) {
+        for (i, led) in stretch.led_until(stretch.len) {
+            points[i] += led;
         }
     }
-    start_distance_race(&mut racers, 2503)
+    points
 }
 
-fn part_b() -> u32 {
-    let rr = RaceRegex::init();
+/// Splits a race into stretches in which nobody starts or stops flying. Rather than ticking every
+/// second, time jumps from one event (any reindeer starting or stopping flying) to the next, and
+/// the seconds each reindeer leads are worked out for the whole stretch in one go, as every
+/// distance is a straight line until the next event.
+///
+/// Over a long race a reindeer that is slower on average falls so far behind that it can never
+/// lead again, and is dropped. Once only one reindeer is left it leads for the rest of the race
+/// in one last stretch, so races of billions of seconds take about as long as the first few
+/// thousand.
+fn race(racers: &[Reindeer], seconds: u64) -> impl Iterator<Item = Stretch> {
+    let retired: Vec<u64> = racers.iter().map(|r| r.retires(racers)).collect();
+    let mut time = 0;
 
-    let mut racers: Vec<Reindeer> = Vec::new();
-    for line in INPUT_A.lines() {
-        if let Some(rd) = rr.parse(line) {
-            racers.push(rd);
+    iter::from_fn(move || {
+        if time >= seconds {
+            return None;
         }
+        let start = time;
+        let active: Vec<usize> = (0..racers.len())
+            .filter(|i| retired[*i] > time + 1)
+            .collect();
+        if let [only] = active[..] {
+            time = seconds;
+            return Some(Stretch {
+                start,
+                len: seconds - start,
+                leads: vec![(only, Some((1, seconds - start)))],
+            });
+        }
+
+        let end = active
+            .iter()
+            .map(|i| racers[*i].next_event(time))
+            .fold(seconds, u64::min);
+        let lines: Vec<(i64, i64)> = active
+            .iter()
+            .map(|i| {
+                let r = &racers[*i];
+                (r.distance_at(time) as i64, r.speed_after(time) as i64)
+            })
+            .collect();
+        let leads = active
+            .iter()
+            .copied()
+            .zip(seconds_leading(&lines, end - time))
+            .collect();
+        time = end;
+        Some(Stretch {
+            start,
+            len: end - start,
+            leads,
+        })
+    })
+}
+
+/// Part of a race in which nobody starts or stops flying
+struct Stretch {
+    /// Seconds into the race that the stretch starts at
+    start: u64,
+    len: u64,
+    /// Every reindeer that could still lead, with the first and last second into the stretch
+    /// that it is in the lead or tied for it, if it ever is
+    leads: Vec<(usize, Option<(u64, u64)>)>,
+}
+
+impl Stretch {
+    /// Every reindeer in the lead `s` seconds into the stretch
+    fn leaders(&self, s: u64) -> Vec<usize> {
+        self.leads
+            .iter()
+            .filter(|(_, lead)| lead.is_some_and(|(first, last)| first <= s && s <= last))
+            .map(|(i, _)| *i)
+            .collect()
+    }
+
+    /// How many of the first `s` seconds of the stretch each reindeer leads
+    fn led_until(&self, s: u64) -> impl Iterator<Item = (usize, u64)> {
+        self.leads.iter().filter_map(move |(i, lead)| match lead {
+            Some((first, last)) if *first <= s => Some((*i, u64::min(*last, s) - first + 1)),
+            _ => None,
+        })
+    }
+
+    /// The seconds into the stretch at which the leaders may be different to the second before:
+    /// its first, and whenever a reindeer takes or loses the lead
+    fn changes(&self) -> Vec<u64> {
+        let mut changes = vec![1];
+        for (first, last) in self.leads.iter().filter_map(|(_, lead)| *lead) {
+            changes.extend([first, last + 1]);
+        }
+        changes.retain(|s| *s <= self.len);
+        changes.sort_unstable();
+        changes.dedup();
+        changes
     }
-    start_points_race(&mut racers, 2503)
 }
 
-fn start_distance_race(racers: &mut [Reindeer], seconds: usize) -> u32 {
-    let mut time: usize = 0;
-    while time < seconds {
-        // Must use explicit mutable iterator that does a reborrow instead of relying on
-        // implicit iterator
-        for r in racers.iter_mut() {
-            if r.left == 0 {
-                r.running = !r.running;
-                r.left = if r.running {
-                    r.run_seconds
-                } else {
-                    r.rest_seconds
+/// Given each reindeer's distance and speed at the start of a stretch of `len` seconds in which
+/// nobody starts or stops flying, finds the first and last of those seconds that each one is in
+/// the lead or tied for it. Reindeer `i` is ahead of or level with `j` after `s` seconds when
+/// `(a_i - a_j) + (v_i - v_j) * s >= 0`, so each rival bounds the seconds it leads from one side,
+/// and they are all in one unbroken run.
+fn seconds_leading(lines: &[(i64, i64)], len: u64) -> Vec<Option<(u64, u64)>> {
+    lines
+        .iter()
+        .map(|(a_i, v_i)| {
+            let (mut lo, mut hi) = (1, len as i64);
+            for (a_j, v_j) in lines {
+                let (da, dv) = (a_i - a_j, v_i - v_j);
+                match dv.signum() {
+                    0 if da < 0 => hi = 0,
+                    1 => lo = i64::max(lo, (dv - 1 - da).div_euclid(dv)),
+                    -1 => hi = i64::min(hi, da.div_euclid(-dv)),
+                    _ => {}
                 }
             }
-            if r.running {
-                r.distance += r.speed
+            (lo <= hi).then_some((lo as u64, hi as u64))
+        })
+        .collect()
+}
+
+fn export_timeline(path: &Path, racers: &[Reindeer], events_only: bool) -> io::Result<()> {
+    let timeline = timeline(racers, RACE_SECONDS, events_only);
+    let mut out = BufWriter::new(File::create(path)?);
+    timeline.write_csv(&mut out)?;
+    out.flush()
+}
+
+/// The state of a race at the end of every second, or only of the seconds in which the lead
+/// changed hands, for charting a race or checking how ties were scored
+struct Timeline {
+    names: Vec<String>,
+    rows: Vec<Standings>,
+}
+
+/// Where every reindeer is at the end of one second
+struct Standings {
+    second: u64,
+    distances: Vec<u64>,
+    points: Vec<u64>,
+    /// Every reindeer in the lead, which is more than one when they are tied
+    leaders: Vec<usize>,
+    /// Whether the leaders are different to the second before
+    lead_changed: bool,
+}
+
+/// Builds the timeline of a race from the same stretches as `points_race`. With `events_only`
+/// there is only a row for each second in which the lead changed hands, including when a
+/// reindeer caught up to tie for it or dropped out of a tie, and the seconds in between are
+/// never looked at.
+fn timeline(racers: &[Reindeer], seconds: u64, events_only: bool) -> Timeline {
+    let mut points = vec![0; racers.len()];
+    let mut previous: Vec<usize> = Vec::new();
+    let mut rows = Vec::new();
+
+    for stretch in race(racers, seconds) {
+        let at: Vec<u64> = if events_only {
+            stretch.changes()
+        } else {
+            (1..=stretch.len).collect()
+        };
+        for s in at {
+            let leaders = stretch.leaders(s);
+            let lead_changed = leaders != previous;
+            if events_only && !lead_changed {
+                continue;
+            }
+
+            let second = stretch.start + s;
+            let mut row_points = points.clone();
+            for (i, led) in stretch.led_until(s) {
+                row_points[i] += led;
             }
-            r.left -= 1;
+            previous = leaders.clone();
+            rows.push(Standings {
+                second,
+                distances: racers.iter().map(|r| r.distance_at(second)).collect(),
+                points: row_points,
+                leaders,
+                lead_changed,
+            });
+        }
+        for (i, led) in stretch.led_until(stretch.len) {
+            points[i] += led;
         }
-        time += 1;
     }
 
-    racers
-        .iter()
-        .fold(0, |farthest, r| u32::max(farthest, r.distance))
-}
-
-fn start_points_race(racers: &mut [Reindeer], seconds: usize) -> u32 {
-    let mut time: usize = 0;
-    let mut farthest: u32 = 0;
-    while time < seconds {
-        // Must use explicit mutable iterator that does a reborrow instead of relying on
-        // implicit iterator
-        for r in racers.iter_mut() {
-            if r.left == 0 {
-                r.running = !r.running;
-                r.left = if r.running {
-                    r.run_seconds
-                } else {
-                    r.rest_seconds
-                }
-            }
-            if r.running {
-                r.distance += r.speed;
-                farthest = u32::max(farthest, r.distance);
+    Timeline {
+        names: racers.iter().map(|r| r.name.clone()).collect(),
+        rows,
+    }
+}
+
+impl Timeline {
+    /// Writes one line per reindeer per row. Position is by distance, and reindeer that are level
+    /// share a position, so two tied leaders are both 1st and the next is 3rd.
+    fn write_csv<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(
+            out,
+            "second,reindeer,position,distance,points,leading,lead_changed"
+        )?;
+        for row in self.rows.iter() {
+            for (i, name) in self.names.iter().enumerate() {
+                let distance = row.distances[i];
+                let position = 1 + row.distances.iter().filter(|d| **d > distance).count();
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{}",
+                    row.second,
+                    csv_field(name),
+                    position,
+                    distance,
+                    row.points[i],
+                    row.leaders.contains(&i),
+                    row.lead_changed
+                )?;
             }
-            r.left -= 1;
+        }
+        Ok(())
+    }
+}
+
+/// Quotes a CSV field if it has a comma, quote or line break in it, doubling any quotes
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Distance of the winning reindeer, found by ticking every second. Kept to check the closed form.
+#[cfg(test)]
+fn start_distance_race(racers: &[Reindeer], seconds: usize) -> u64 {
+    let mut state: Vec<Tick> = racers.iter().map(|_| Tick::default()).collect();
+    for _ in 0..seconds {
+        for (r, t) in racers.iter().zip(state.iter_mut()) {
+            t.advance(r);
+        }
+    }
+
+    state
+        .iter()
+        .fold(0, |farthest, t| u64::max(farthest, t.distance))
+}
+
+/// Points of each reindeer, found by ticking every second. Kept to check the event driven race.
+#[cfg(test)]
+fn start_points_race(racers: &[Reindeer], seconds: usize) -> Vec<u64> {
+    let mut state: Vec<Tick> = racers.iter().map(|_| Tick::default()).collect();
+    for _ in 0..seconds {
+        for (r, t) in racers.iter().zip(state.iter_mut()) {
+            t.advance(r);
         }
 
         // Now give points to those that are the farthest
-        for r in racers.iter_mut() {
-            if r.distance == farthest {
-                r.points += 1;
+        let farthest = state.iter().map(|t| t.distance).max().unwrap_or(0);
+        for t in state.iter_mut() {
+            if t.distance == farthest {
+                t.points += 1;
             }
         }
-        time += 1;
     }
 
-    racers.iter().fold(0, |most, r| u32::max(most, r.points))
+    state.iter().map(|t| t.points).collect()
 }
 
-struct Reindeer {
-    _name: String,
-    speed: u32,
-    run_seconds: u32,
-    rest_seconds: u32,
+/// Where a reindeer is in a race that is ticked every second
+#[cfg(test)]
+#[derive(Default)]
+struct Tick {
     running: bool,
-    left: u32,
-    distance: u32,
-    points: u32,
-}
-
-struct RaceRegex {
-    regex: Regex,
-}
-
-impl RaceRegex {
-    fn init() -> RaceRegex {
-        RaceRegex {
-            regex: Regex::new(INPUT_REGEX).unwrap(),
-        }
-    }
-
-    fn parse(&self, s: &str) -> Option<Reindeer> {
-        self.regex.captures(s).map(|c| {
-            // Name is not actually used
-            let _name = String::from(c.name("name").unwrap().as_str());
-            let s_speed = c.name("speed").unwrap().as_str();
-            let s_run = c.name("run").unwrap().as_str();
-            let s_rest = c.name("rest").unwrap().as_str();
-
-            let speed = s_speed.parse::<u32>().unwrap();
-            let run_seconds = s_run.parse::<u32>().unwrap();
-            let rest_seconds = s_rest.parse::<u32>().unwrap();
-            Reindeer {
-                _name,
-                speed,
-                run_seconds,
-                rest_seconds,
-                running: false,
-                left: 0,
-                distance: 0,
-                points: 0,
+    left: u64,
+    distance: u64,
+    points: u64,
+}
+
+#[cfg(test)]
+impl Tick {
+    fn advance(&mut self, r: &Reindeer) {
+        if self.left == 0 {
+            self.running = !self.running;
+            self.left = if self.running {
+                r.run_seconds
+            } else {
+                r.rest_seconds
             }
-        })
+        }
+        if self.running {
+            self.distance += r.speed
+        }
+        self.left -= 1;
+    }
+}
+
+line_parser! {
+    struct Reindeer = INPUT_REGEX {
+        name: String,
+        speed: u64,
+        run_seconds: u64,
+        rest_seconds: u64,
     }
 }
 
-const INPUT_REGEX: &str = "^(?<name>[a-zA-Z]+) can fly (?<speed>[0-9]+) km/s for (?<run>[0-9]+) seconds, but then must rest for (?<rest>[0-9]+) seconds\\.$";
+impl Reindeer {
+    /// Length of one cycle of flying and resting. A reindeer that neither flies nor rests is
+    /// taken to rest a second at a time, so it stays where it is.
+    fn cycle(&self) -> u64 {
+        u64::max(self.run_seconds + self.rest_seconds, 1)
+    }
+
+    /// Distance flown in one full cycle of flying and resting
+    fn burst(&self) -> u64 {
+        self.speed * self.run_seconds
+    }
+
+    /// Distance flown after `t` seconds: every full cycle, plus whatever it has flown of the
+    /// current one
+    fn distance_at(&self, t: u64) -> u64 {
+        let cycles = t / self.cycle();
+        let flying = u64::min(t % self.cycle(), self.run_seconds);
+        cycles * self.burst() + flying * self.speed
+    }
+
+    /// Speed during the second after `t`
+    fn speed_after(&self, t: u64) -> u64 {
+        if t % self.cycle() < self.run_seconds {
+            self.speed
+        } else {
+            0
+        }
+    }
+
+    /// The next time after `t` that this reindeer starts or stops flying, or u64::MAX if it never
+    /// flies
+    fn next_event(&self, t: u64) -> u64 {
+        if self.run_seconds == 0 {
+            return u64::MAX;
+        }
+        let into_cycle = t % self.cycle();
+        if into_cycle < self.run_seconds {
+            t + self.run_seconds - into_cycle
+        } else {
+            t + self.cycle() - into_cycle
+        }
+    }
+
+    /// The first second from which this reindeer can never lead again, or u64::MAX if it could
+    /// always catch up. Averaged over a cycle a reindeer flies `burst / cycle` km/s, and it is
+    /// never behind that average and at most `burst * rest / cycle` km ahead of it, just as it
+    /// stops flying. So once a rival that is faster on average has gained more than that, this
+    /// reindeer is behind for good.
+    fn retires(&self, racers: &[Reindeer]) -> u64 {
+        let (burst, cycle) = (u128::from(self.burst()), u128::from(self.cycle()));
+        let lead = burst * u128::from(self.rest_seconds);
+        racers
+            .iter()
+            .filter_map(|rival| {
+                let (r_burst, r_cycle) = (u128::from(rival.burst()), u128::from(rival.cycle()));
+                // Compares the average speeds without dividing: rival gains `gain / (cycle *
+                // r_cycle)` km/s, and needs to be `lead / cycle` km ahead
+                let gain = r_burst * cycle;
+                let loss = burst * r_cycle;
+                (gain > loss).then(|| lead * r_cycle / (gain - loss) + 1)
+            })
+            .min()
+            .map_or(u64::MAX, |t| u64::try_from(t).unwrap_or(u64::MAX))
+    }
+}
+
+/// How long the race lasts
+const RACE_SECONDS: u64 = 2503;
+
+const TIMELINE_VAR: &str = "AOC_RACE_TIMELINE";
+
+const TIMELINE_EVENTS_VAR: &str = "AOC_RACE_TIMELINE_EVENTS";
+
+const INPUT_REGEX: &str = "^(?<name>[a-zA-Z]+) can fly (?<speed>[0-9]+) km/s for (?<run_seconds>[0-9]+) seconds, but then must rest for (?<rest_seconds>[0-9]+) seconds\\.$";
 const _INPUT_SAMPLE: &str = indoc! {r#"
 Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.
 Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds.
@@ -222,17 +522,155 @@ Dancer can fly 7 km/s for 20 seconds, but then must rest for 119 seconds."#};
 
 #[cfg(test)]
 mod tests {
-    use crate::year_2015::day_14::{part_a, part_b};
+    use crate::year_2015::day_14::{
+        _INPUT_SAMPLE, INPUT_A, RACE_SECONDS, Reindeer, distance_race, parse_input, part_a, part_b,
+        points_race, start_distance_race, start_points_race, timeline,
+    };
+    use indoc::indoc;
 
     #[test]
     fn test_a() {
-        let answer = part_a();
+        let answer = part_a(&parse_input(INPUT_A));
         assert_eq!(2696, answer);
     }
 
     #[test]
     fn test_b() {
-        let answer = part_b();
+        let answer = part_b(&parse_input(INPUT_A));
         assert_eq!(1084, answer);
     }
+
+    #[test]
+    fn test_sample() {
+        let racers = parse_input(_INPUT_SAMPLE);
+        assert_eq!(racers[0].distance_at(1000), 1120);
+        assert_eq!(racers[1].distance_at(1000), 1056);
+        assert_eq!(points_race(&racers, 1000), vec![312, 689]);
+    }
+
+    #[test]
+    fn test_against_ticks() {
+        for input in [_INPUT_SAMPLE, INPUT_A] {
+            let racers = parse_input(input);
+            for seconds in [0, 1, 10, 11, 12, 137, 138, 140, 1000, 2503, 20000] {
+                assert_eq!(
+                    distance_race(&racers, seconds),
+                    start_distance_race(&racers, seconds as usize)
+                );
+                assert_eq!(
+                    points_race(&racers, seconds),
+                    start_points_race(&racers, seconds as usize)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_long_race() {
+        // Comet is faster on average, so once Dancer has fallen behind for good every second is
+        // Comet's
+        let racers = parse_input(_INPUT_SAMPLE);
+        let early = start_points_race(&racers, 100_000);
+        let points = points_race(&racers, 1_000_000_000);
+        assert_eq!(points, vec![early[0] + 1_000_000_000 - 100_000, early[1]]);
+        assert_eq!(racers[0].distance_at(1_000_000_000), 1_021_897_940);
+    }
+
+    #[test]
+    fn test_timeline() {
+        let racers = parse_input(_INPUT_SAMPLE);
+        let every = timeline(&racers, 1000, false);
+        assert_eq!(every.rows.len(), 1000);
+        let last = every.rows.last().unwrap();
+        assert_eq!(last.distances, vec![1120, 1056]);
+        assert_eq!(last.points, points_race(&racers, 1000));
+
+        // Dancer leads from the start, until Comet's second burst, and then Dancer's second burst
+        let changes: Vec<(u64, Vec<usize>)> = timeline(&racers, 1000, true)
+            .rows
+            .into_iter()
+            .map(|row| (row.second, row.leaders))
+            .collect();
+        assert_eq!(changes[..3], [(1, vec![1]), (140, vec![0]), (180, vec![1])]);
+    }
+
+    #[test]
+    fn test_timeline_events() {
+        // Only writing the lead changes gives the same rows as picking them out of every second
+        for input in [_INPUT_SAMPLE, INPUT_A] {
+            let racers = parse_input(input);
+            let every = timeline(&racers, RACE_SECONDS, false);
+            let events = timeline(&racers, RACE_SECONDS, true);
+            let picked: Vec<_> = every.rows.iter().filter(|row| row.lead_changed).collect();
+            assert_eq!(events.rows.len(), picked.len());
+            for (a, b) in events.rows.iter().zip(picked) {
+                assert_eq!(a.second, b.second);
+                assert_eq!(a.distances, b.distances);
+                assert_eq!(a.points, b.points);
+                assert_eq!(a.leaders, b.leaders);
+            }
+            for row in every.rows.iter().step_by(97) {
+                assert_eq!(row.points, start_points_race(&racers, row.second as usize));
+            }
+        }
+    }
+
+    #[test]
+    fn test_timeline_csv() {
+        let racers = parse_input(_INPUT_SAMPLE);
+        let mut out: Vec<u8> = Vec::new();
+        timeline(&racers, 140, true).write_csv(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            indoc! {"
+                second,reindeer,position,distance,points,leading,lead_changed
+                1,Comet,2,14,0,false,true
+                1,Dancer,1,16,1,true,true
+                140,Comet,1,182,1,true,true
+                140,Dancer,2,176,139,false,true
+            "}
+        );
+    }
+
+    #[test]
+    fn test_never_flies() {
+        // A reindeer that neither flies nor rests stays at the start
+        let mut racers = parse_input(_INPUT_SAMPLE);
+        racers.push(Reindeer {
+            name: String::from("Rudolph"),
+            speed: 10,
+            run_seconds: 0,
+            rest_seconds: 0,
+        });
+        assert_eq!(racers[2].distance_at(1000), 0);
+        assert_eq!(distance_race(&racers, 1000), 1120);
+        assert_eq!(points_race(&racers, 1000), vec![312, 689, 0]);
+        assert_eq!(
+            timeline(&racers, 1000, true).rows.last().unwrap().distances[2],
+            0
+        );
+    }
+
+    #[test]
+    fn test_timeline_csv_quoting() {
+        let racers: Vec<Reindeer> = ["Rudolph, Jr", "The \"Red\" One"]
+            .into_iter()
+            .map(|name| Reindeer {
+                name: String::from(name),
+                speed: 10,
+                run_seconds: 1,
+                rest_seconds: 1,
+            })
+            .collect();
+        let mut out: Vec<u8> = Vec::new();
+        timeline(&racers, 1, false).write_csv(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            indoc! {r#"
+                second,reindeer,position,distance,points,leading,lead_changed
+                1,"Rudolph, Jr",1,10,1,true,true
+                1,"The ""Red"" One",1,10,1,true,true
+            "#}
+        );
+    }
 }
@@ -55,15 +55,22 @@
 /// highest-scoring cookie you can make with a calorie total of 500?
 ///
 /// Your puzzle answer was 1766400.
+use crate::line_parser::line_parser;
 use indoc::indoc;
-use regex::Regex;
+use num_bigint::BigInt;
+use std::mem;
 use std::time::SystemTime;
 
 pub fn run() {
     println!("--- Day 15: Science for Hungry People --- ");
 
     let now = SystemTime::now();
-    let answer_a = part_a();
+    let kitchen = Kitchen::parse(INPUT_A).expect("Invalid input");
+    let duration = now.elapsed().expect("Elapsed failed");
+    println!("Parsed the ingredients\n in {}ms", duration.as_millis());
+
+    let now = SystemTime::now();
+    let answer_a = part_a(&kitchen);
     let duration = now.elapsed().expect("Elapsed failed");
     println!(
         "Given the ingredients in your kitchen and their properties, what is the total score of the highest-scoring cookie you can make?\n {}\n in {}ms",
@@ -72,7 +79,7 @@ pub fn run() {
     );
 
     let now = SystemTime::now();
-    let answer_b = part_b();
+    let answer_b = part_b(&kitchen);
     let duration = now.elapsed().expect("Elapsed failed");
     println!(
         "Given the ingredients in your kitchen and their properties, what is the total score of the highest-scoring cookie you can make with a calorie total of 500\n {}\n in {}ms",
@@ -81,114 +88,402 @@ pub fn run() {
     );
 }
 
-fn part_a() -> i64 {
-    let ir = IngredientRegex::init();
+fn part_a(kitchen: &Kitchen) -> BigInt {
+    let objective = kitchen.objective(SCORE).unwrap();
+    let best = kitchen.best_recipe(100, &objective, &[]).unwrap();
+    best.score
+}
+
+fn part_b(kitchen: &Kitchen) -> BigInt {
+    let objective = kitchen.objective(SCORE).unwrap();
+    let constraints = kitchen.constraints("calories = 500").unwrap();
+    let best = kitchen.best_recipe(100, &objective, &constraints).unwrap();
+    best.score
+}
+
+/// How the puzzle scores a cookie
+const SCORE: &str = "product capacity durability flavor texture";
+
+/// Most ingredients that are searched exactly when there are no constraints. The number of
+/// recipes grows with the power of the ingredient count, so past this `hill_climb` is used, and
+/// the recipe it finds may not be the best. With constraints every recipe is still searched,
+/// however many ingredients there are, as the local search could not be sure of meeting them. So
+/// a big kitchen with constraints is right but may be very slow.
+const EXACT_SEARCH_LIMIT: usize = 6;
+
+/// The ingredients, and the properties they were listed with. Properties are referred to by their
+/// index in `properties`, which is also the index of the amount in each ingredient.
+struct Kitchen {
+    properties: Vec<String>,
+    ingredients: Vec<Ingredient>,
+}
+
+struct Ingredient {
+    /// Amount of each property per teaspoon
+    amounts: Vec<i64>,
+}
+
+/// What makes one recipe better than another. Written as text, either `product <property>...`
+/// or `total <property>`.
+enum Objective {
+    /// The product of the totals of these properties, where negative totals count as 0. It is
+    /// worked out as a big integer, as a few large totals soon overflow an i64.
+    Product(Vec<usize>),
+    /// The total of a single property
+    Total(usize),
+}
+
+/// A limit on the total of a property. Written as text, one per statement separated by
+/// semicolons, as `<property> = <n>`, `<property> >= <n>` or `<property> <= <n>`.
+enum Constraint {
+    Equal(usize, i64),
+    AtLeast(usize, i64),
+    AtMost(usize, i64),
+}
+
+/// The best recipe found, with the teaspoons of each ingredient in the order they were listed
+#[derive(Debug, PartialEq)]
+struct Best {
+    quantities: Vec<i64>,
+    score: BigInt,
+}
+
+impl Kitchen {
+    /// Reads one ingredient per line. Every ingredient must have the same properties as the first,
+    /// although they may be listed in any order, and each property only once.
+    fn parse(input: &str) -> Result<Kitchen, String> {
+        let lines = IngredientLine::parser()
+            .parse_lines(input)
+            .map_err(|e| e.to_string())?;
+        let property_parser = PropertyAmount::parser();
+        let mut properties: Vec<String> = Vec::new();
+        let mut ingredients = Vec::new();
+
+        for line in lines {
+            let name = line.name;
+            let listed = line
+                .properties
+                .split(", ")
+                .map(|p| property_parser.parse(p))
+                .collect::<Result<Vec<PropertyAmount>, _>>()
+                .map_err(|e| format!("{name}: {e}"))?;
+            if ingredients.is_empty() {
+                properties = listed.iter().map(|p| p.property.clone()).collect();
+            }
+            if listed.len() != properties.len() {
+                return Err(format!(
+                    "{name} does not have the same properties as the others"
+                ));
+            }
 
-    let mut ingredients: Vec<Ingredient> = Vec::new();
-    for line in INPUT_A.lines() {
-        if let Some(rd) = ir.parse(line) {
-            ingredients.push(rd);
+            let mut amounts = vec![0; properties.len()];
+            let mut seen = vec![false; properties.len()];
+            for PropertyAmount { property, amount } in listed {
+                let p = properties
+                    .iter()
+                    .position(|known| *known == property)
+                    .ok_or_else(|| format!("{name} has an unknown property {property}"))?;
+                if mem::replace(&mut seen[p], true) {
+                    return Err(format!("{name} has the property {property} more than once"));
+                }
+                amounts[p] = amount;
+            }
+            ingredients.push(Ingredient { amounts });
         }
+        Ok(Kitchen {
+            properties,
+            ingredients,
+        })
     }
 
-    let mut r = Recipe::init(ingredients, 100, 0);
-    let mut best_score: i64 = 0;
+    fn property(&self, name: &str) -> Result<usize, String> {
+        self.properties
+            .iter()
+            .position(|p| p == name)
+            .ok_or_else(|| format!("Unknown property {name}"))
+    }
 
-    while !r.done {
-        r.advance_part_a();
-        let s = r.score();
-        best_score = i64::max(best_score, s);
+    fn objective(&self, s: &str) -> Result<Objective, String> {
+        let mut words = s.split_whitespace();
+        let properties = |words: std::str::SplitWhitespace| {
+            words
+                .map(|w| self.property(w))
+                .collect::<Result<Vec<usize>, String>>()
+        };
+        match (words.next(), properties(words)?.as_slice()) {
+            (Some("product"), properties) if !properties.is_empty() => {
+                Ok(Objective::Product(properties.to_vec()))
+            }
+            (Some("total"), [p]) => Ok(Objective::Total(*p)),
+            _ => Err(format!("Invalid objective: {s}")),
+        }
     }
-    best_score
-}
 
-fn part_b() -> i64 {
-    let ir = IngredientRegex::init();
+    fn constraints(&self, s: &str) -> Result<Vec<Constraint>, String> {
+        s.split(';')
+            .map(str::trim)
+            .filter(|statement| !statement.is_empty())
+            .map(|statement| {
+                let words: Vec<&str> = statement.split_whitespace().collect();
+                let [property, op, value] = words[..] else {
+                    return Err(format!("Invalid constraint: {statement}"));
+                };
+                let p = self.property(property)?;
+                let v = value
+                    .parse::<i64>()
+                    .map_err(|_| format!("Invalid amount {value:?}"))?;
+                match op {
+                    "=" => Ok(Constraint::Equal(p, v)),
+                    ">=" => Ok(Constraint::AtLeast(p, v)),
+                    "<=" => Ok(Constraint::AtMost(p, v)),
+                    _ => Err(format!("Unknown comparison: {op}")),
+                }
+            })
+            .collect()
+    }
 
-    let mut ingredients: Vec<Ingredient> = Vec::new();
-    for line in INPUT_A.lines() {
-        if let Some(rd) = ir.parse(line) {
-            ingredients.push(rd);
+    /// Finds the best recipe using exactly `teaspoons` that meets every constraint, or None if no
+    /// recipe can. Past `EXACT_SEARCH_LIMIT` ingredients without constraints it is only a good
+    /// recipe rather than the best.
+    fn best_recipe(
+        &self,
+        teaspoons: i64,
+        objective: &Objective,
+        constraints: &[Constraint],
+    ) -> Option<Best> {
+        if constraints.is_empty() && self.ingredients.len() > EXACT_SEARCH_LIMIT {
+            Some(self.hill_climb(teaspoons, objective))
+        } else {
+            PrunedSearch::new(self, objective, constraints).best(teaspoons)
         }
     }
 
-    let mut r = Recipe::init(ingredients, 100, 500);
-    let mut best_score: i64 = 0;
+    fn totals(&self, quantities: &[i64]) -> Vec<i64> {
+        let mut totals = vec![0; self.properties.len()];
+        for (ingredient, q) in self.ingredients.iter().zip(quantities) {
+            add_teaspoons(&mut totals, &ingredient.amounts, *q);
+        }
+        totals
+    }
 
-    while !r.done {
-        r.advance_part_b();
-        let s = r.score();
-        best_score = i64::max(best_score, s);
+    /// Improves an evenly split recipe by moving teaspoons from one ingredient to another while
+    /// that helps, starting with big moves and finishing with single teaspoons. This finds a good
+    /// recipe quickly for any number of ingredients, but may stop at one that is only locally the
+    /// best.
+    fn hill_climb(&self, teaspoons: i64, objective: &Objective) -> Best {
+        let n = self.ingredients.len() as i64;
+        let mut quantities: Vec<i64> = (0..n)
+            .map(|i| teaspoons / n + i64::from(i < teaspoons % n))
+            .collect();
+
+        // While every recipe nearby scores the same there is nothing to climb, so how far the
+        // properties in the objective fall below 0 breaks the tie, pointing towards better recipes
+        let rank = |quantities: &[i64]| {
+            let totals = self.totals(quantities);
+            (objective.score(&totals), objective.shortfall(&totals))
+        };
+
+        let mut current = rank(&quantities);
+        let mut step = i64::max(teaspoons / i64::max(n, 1) / 2, 1);
+        loop {
+            let mut improved = false;
+            for from in 0..quantities.len() {
+                for to in 0..quantities.len() {
+                    if from == to || quantities[from] < step {
+                        continue;
+                    }
+                    quantities[from] -= step;
+                    quantities[to] += step;
+                    let moved = rank(&quantities);
+                    if moved > current {
+                        current = moved;
+                        improved = true;
+                    } else {
+                        quantities[from] += step;
+                        quantities[to] -= step;
+                    }
+                }
+            }
+            if !improved {
+                if step == 1 {
+                    break;
+                }
+                step /= 2;
+            }
+        }
+        Best {
+            quantities,
+            score: current.0,
+        }
     }
-    best_score
 }
 
-struct Ingredient {
-    _name: String,
-    capacity: i64,
-    durability: i64,
-    flavor: i64,
-    texture: i64,
-    calories: i64,
+impl Objective {
+    fn score(&self, totals: &[i64]) -> BigInt {
+        match self {
+            Objective::Product(properties) => properties
+                .iter()
+                .map(|p| BigInt::from(i64::max(0, totals[*p])))
+                .product(),
+            Objective::Total(p) => BigInt::from(totals[*p]),
+        }
+    }
+
+    /// How far below 0 the totals that would zero the score are
+    fn shortfall(&self, totals: &[i64]) -> i64 {
+        match self {
+            Objective::Product(properties) => {
+                properties.iter().map(|p| i64::min(0, totals[*p])).sum()
+            }
+            Objective::Total(_) => 0,
+        }
+    }
+}
+
+impl Constraint {
+    /// Whether a recipe whose totals will end up between `low` and `high` could meet this
+    fn allows(&self, low: &[i64], high: &[i64]) -> bool {
+        match self {
+            Constraint::Equal(p, v) => low[*p] <= *v && *v <= high[*p],
+            Constraint::AtLeast(p, v) => high[*p] >= *v,
+            Constraint::AtMost(p, v) => low[*p] <= *v,
+        }
+    }
 }
 
-struct IngredientRegex {
-    regex: Regex,
+/// Enumerates only the recipes that use exactly the requested teaspoons, by giving each
+/// ingredient in turn some of what is left and the last ingredient all of the rest. A branch is
+/// abandoned when it can no longer meet the constraints, or when its best possible score, using
+/// the most each property could still grow by, is no better than the best recipe found so far.
+/// For the product of properties that includes every branch where one can no longer be positive.
+struct PrunedSearch<'a> {
+    kitchen: &'a Kitchen,
+    objective: &'a Objective,
+    constraints: &'a [Constraint],
+    /// Smallest and largest amount of each property per teaspoon among the ingredient at each
+    /// index and all that come after it
+    least: Vec<Vec<i64>>,
+    most: Vec<Vec<i64>>,
+    quantities: Vec<i64>,
+    best: Option<Best>,
 }
 
-impl IngredientRegex {
-    fn init() -> IngredientRegex {
-        IngredientRegex {
-            regex: Regex::new(INPUT_REGEX).unwrap(),
+impl<'a> PrunedSearch<'a> {
+    fn new(
+        kitchen: &'a Kitchen,
+        objective: &'a Objective,
+        constraints: &'a [Constraint],
+    ) -> PrunedSearch<'a> {
+        let n = kitchen.ingredients.len();
+        let properties = kitchen.properties.len();
+        let mut least = vec![vec![i64::MAX; properties]; n + 1];
+        let mut most = vec![vec![i64::MIN; properties]; n + 1];
+        for i in (0..n).rev() {
+            for (p, amount) in kitchen.ingredients[i].amounts.iter().enumerate() {
+                least[i][p] = i64::min(least[i + 1][p], *amount);
+                most[i][p] = i64::max(most[i + 1][p], *amount);
+            }
+        }
+
+        PrunedSearch {
+            kitchen,
+            objective,
+            constraints,
+            least,
+            most,
+            quantities: vec![0; n],
+            best: None,
+        }
+    }
+
+    fn best(mut self, teaspoons: i64) -> Option<Best> {
+        if !self.kitchen.ingredients.is_empty() {
+            let totals = vec![0; self.kitchen.properties.len()];
+            self.visit(0, teaspoons, &totals);
         }
+        self.best
     }
 
-    fn parse(&self, s: &str) -> Option<Ingredient> {
-        self.regex.captures(s).map(|c| {
-            // Name is not actually used
-            let _name = String::from(c.name("name").unwrap().as_str());
-            let capacity = c.name("cap").unwrap().as_str().parse::<i64>().unwrap();
-            let durability = c.name("dur").unwrap().as_str().parse::<i64>().unwrap();
-            let flavor = c.name("fla").unwrap().as_str().parse::<i64>().unwrap();
-            let texture = c.name("tex").unwrap().as_str().parse::<i64>().unwrap();
-            let calories = c.name("cal").unwrap().as_str().parse::<i64>().unwrap();
-
-            Ingredient {
-                _name,
-                capacity,
-                durability,
-                flavor,
-                texture,
-                calories,
+    fn visit(&mut self, i: usize, left: i64, totals: &[i64]) {
+        let amounts = &self.kitchen.ingredients[i].amounts;
+        if i == self.kitchen.ingredients.len() - 1 {
+            let mut totals = totals.to_vec();
+            add_teaspoons(&mut totals, amounts, left);
+            self.quantities[i] = left;
+            if self.constraints.iter().all(|c| c.allows(&totals, &totals)) {
+                let score = self.objective.score(&totals);
+                if self.best.as_ref().is_none_or(|best| score > best.score) {
+                    self.best = Some(Best {
+                        quantities: self.quantities.clone(),
+                        score,
+                    });
+                }
             }
-        })
+            return;
+        }
+
+        let mut low = totals.to_vec();
+        let mut high = totals.to_vec();
+        add_teaspoons(&mut low, &self.least[i], left);
+        add_teaspoons(&mut high, &self.most[i], left);
+        if !self.constraints.iter().all(|c| c.allows(&low, &high)) {
+            return;
+        }
+        if let Some(best) = self.best.as_ref()
+            && self.objective.score(&high) <= best.score
+        {
+            return;
+        }
+
+        let mut next = totals.to_vec();
+        for q in 0..=left {
+            self.quantities[i] = q;
+            self.visit(i + 1, left - q, &next);
+            add_teaspoons(&mut next, amounts, 1);
+        }
+    }
+}
+
+fn add_teaspoons(totals: &mut [i64], per_teaspoon: &[i64], teaspoons: i64) {
+    for (total, amount) in totals.iter_mut().zip(per_teaspoon) {
+        *total += amount * teaspoons;
+    }
+}
+
+line_parser! {
+    /// An ingredient, with its properties still to be split up
+    struct IngredientLine = INPUT_REGEX {
+        name: String,
+        properties: String,
     }
 }
 
+line_parser! {
+    struct PropertyAmount = PROPERTY_REGEX {
+        property: String,
+        amount: i64,
+    }
+}
+
+/// Tries every quantity of every ingredient, and is kept to check the other searches against
+#[cfg(test)]
 struct Recipe {
     quantities: Vec<i64>,
-    ingredients: Vec<Ingredient>,
     requested_teaspoons: i64,
-    requested_calories: i64,
     done: bool,
 }
 
+#[cfg(test)]
 impl Recipe {
-    fn init(
-        ingredients: Vec<Ingredient>,
-        requested_teaspoons: i64,
-        requested_calories: i64,
-    ) -> Recipe {
+    fn init(ingredients: usize, requested_teaspoons: i64) -> Recipe {
         Recipe {
-            quantities: vec![0; ingredients.len()],
-            ingredients,
+            quantities: vec![0; ingredients],
             requested_teaspoons,
-            requested_calories,
             done: false,
         }
     }
 
-    fn advance_part_a(&mut self) {
+    fn advance(&mut self) {
         loop {
             if self.done {
                 break;
@@ -203,24 +498,6 @@ impl Recipe {
         }
     }
 
-    fn advance_part_b(&mut self) {
-        loop {
-            if self.done {
-                break;
-            }
-            self.tick();
-
-            // Stop advancing if we have a full recipe AND it has a total of 500 calories
-            let sum: i64 = self.quantities.iter().sum();
-            if sum == self.requested_teaspoons {
-                let cal = self.calculate_calories();
-                if cal == self.requested_calories {
-                    break;
-                }
-            }
-        }
-    }
-
     fn tick(&mut self) {
         let mut i: usize = self.quantities.len() - 1;
 
@@ -238,37 +515,10 @@ impl Recipe {
             }
         }
     }
-
-    fn calculate_calories(&self) -> i64 {
-        let mut ret = 0i64;
-        for i in 0..self.quantities.len() {
-            let ic = self.ingredients[i].calories;
-            let iq = self.quantities[i];
-            ret += ic * iq;
-        }
-        ret
-    }
-
-    fn score(&self) -> i64 {
-        let mut cap = 0;
-        let mut dur = 0;
-        let mut fla = 0;
-        let mut tex = 0;
-
-        for i in 0..self.ingredients.len() {
-            let ingredient = &self.ingredients[i];
-            let q = self.quantities[i];
-            cap += ingredient.capacity * q;
-            dur += ingredient.durability * q;
-            fla += ingredient.flavor * q;
-            tex += ingredient.texture * q;
-        }
-
-        i64::max(0, cap) * i64::max(0, dur) * i64::max(0, fla) * i64::max(0, tex)
-    }
 }
 
-const INPUT_REGEX: &str = "^(?<name>[a-zA-Z]+): capacity (?<cap>[-]?[0-9]+), durability (?<dur>[-]?[0-9]+), flavor (?<fla>[-]?[0-9]+), texture (?<tex>[-]?[0-9]+), calories (?<cal>[-]?[0-9]+)$";
+const INPUT_REGEX: &str = "^(?<name>[a-zA-Z]+): (?<properties>.+)$";
+const PROPERTY_REGEX: &str = "^(?<property>[a-z]+) (?<amount>[-]?[0-9]+)$";
 const _INPUT_SAMPLE: &str = indoc! {r#"
 Butterscotch: capacity -1, durability -2, flavor 6, texture 3, calories 8
 Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3
@@ -282,17 +532,141 @@ Candy: capacity 0, durability -1, flavor 0, texture 5, calories 8"#};
 
 #[cfg(test)]
 mod tests {
-    use crate::year_2015::day_15::{part_a, part_b};
+    use crate::year_2015::day_15::{
+        _INPUT_SAMPLE, Best, Constraint, INPUT_A, Kitchen, Objective, Recipe, SCORE, part_a, part_b,
+    };
+    use num_bigint::BigInt;
 
     #[test]
     fn test_a() {
-        let answer = part_a();
-        assert_eq!(21367368, answer);
+        let answer = part_a(&Kitchen::parse(INPUT_A).unwrap());
+        assert_eq!(BigInt::from(21367368), answer);
     }
 
     #[test]
     fn test_b() {
-        let answer = part_b();
-        assert_eq!(1766400, answer);
+        let answer = part_b(&Kitchen::parse(INPUT_A).unwrap());
+        assert_eq!(BigInt::from(1766400), answer);
+    }
+
+    /// The best recipe found by trying every one
+    fn exhaustive(
+        kitchen: &Kitchen,
+        teaspoons: i64,
+        objective: &Objective,
+        constraints: &[Constraint],
+    ) -> Option<Best> {
+        let mut r = Recipe::init(kitchen.ingredients.len(), teaspoons);
+        let mut best: Option<Best> = None;
+        while !r.done {
+            r.advance();
+            if r.quantities.iter().sum::<i64>() != teaspoons {
+                continue;
+            }
+            let totals = kitchen.totals(&r.quantities);
+            if constraints.iter().all(|c| c.allows(&totals, &totals)) {
+                let score = objective.score(&totals);
+                if best.as_ref().is_none_or(|b| score > b.score) {
+                    best = Some(Best {
+                        quantities: r.quantities.clone(),
+                        score,
+                    });
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_sample() {
+        let kitchen = Kitchen::parse(_INPUT_SAMPLE).unwrap();
+        let objective = kitchen.objective(SCORE).unwrap();
+        let calories = kitchen.constraints("calories = 500").unwrap();
+
+        let best = kitchen.best_recipe(100, &objective, &[]);
+        assert_eq!(
+            best,
+            Some(Best {
+                quantities: vec![44, 56],
+                score: BigInt::from(62842880)
+            })
+        );
+        let best = kitchen.best_recipe(100, &objective, &calories);
+        assert_eq!(
+            best,
+            Some(Best {
+                quantities: vec![40, 60],
+                score: BigInt::from(57600000)
+            })
+        );
+        assert_eq!(
+            kitchen.hill_climb(100, &objective).score,
+            BigInt::from(62842880)
+        );
+    }
+
+    #[test]
+    fn test_against_exhaustive() {
+        for input in [_INPUT_SAMPLE, INPUT_A] {
+            let kitchen = Kitchen::parse(input).unwrap();
+            let product = kitchen.objective(SCORE).unwrap();
+            let flavor = kitchen.objective("total flavor").unwrap();
+
+            for teaspoons in [1, 7, 20, 30] {
+                let constraint_sets = [
+                    String::new(),
+                    format!("calories = {}", teaspoons * 5),
+                    format!("calories <= {}; texture >= 1", teaspoons * 4),
+                ];
+                for objective in [&product, &flavor] {
+                    for constraints in constraint_sets.iter() {
+                        let constraints = &kitchen.constraints(constraints).unwrap();
+                        let pruned = kitchen.best_recipe(teaspoons, objective, constraints);
+                        let expected = exhaustive(&kitchen, teaspoons, objective, constraints);
+                        assert_eq!(pruned.map(|b| b.score), expected.map(|b| b.score));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hill_climb() {
+        let kitchen = Kitchen::parse(INPUT_A).unwrap();
+        let objective = kitchen.objective(SCORE).unwrap();
+        assert_eq!(
+            kitchen.hill_climb(100, &objective).score,
+            BigInt::from(21367368)
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let kitchen = Kitchen::parse("A: x 1, y -2\nB: y 3, x 4").unwrap();
+        assert_eq!(kitchen.properties, vec!["x", "y"]);
+        assert_eq!(kitchen.ingredients[1].amounts, vec![4, 3]);
+        assert!(kitchen.property("z").is_err());
+        assert!(kitchen.objective("product").is_err());
+        assert!(kitchen.objective("total x y").is_err());
+        assert!(kitchen.objective("product x z").is_err());
+        assert!(kitchen.constraints("x > 1").is_err());
+        assert!(kitchen.constraints("x = one").is_err());
+        assert!(kitchen.constraints("x = 1 2").is_err());
+
+        assert!(Kitchen::parse("A: x 1, y -2\nB: x 4").is_err());
+        assert!(Kitchen::parse("A: x 1\nB: z 4").is_err());
+        assert!(Kitchen::parse("A: x one").is_err());
+        // The same property twice, even when the count matches the first ingredient
+        assert!(Kitchen::parse("A: x 1, x 2").is_err());
+        assert!(Kitchen::parse("A: x 1, y 2\nB: x 3, x 4").is_err());
+    }
+
+    #[test]
+    fn test_large_scores() {
+        // The product is 10^40, far beyond an i64 or even an i128
+        let kitchen = Kitchen::parse("A: a 10, b 10, c 10, d 10").unwrap();
+        let objective = kitchen.objective("product a b c d").unwrap();
+        let best = kitchen.best_recipe(1_000_000_000, &objective, &[]).unwrap();
+        assert_eq!(best.score, BigInt::from(10).pow(40));
     }
 }
@@ -36,14 +36,27 @@
 //! In your puzzle input, and instead reading by columns, how many of the listed triangles are possible?
 //!
 //! Your puzzle answer was 1838.
+use crate::algorithm::{column_groups, valid_polygon};
+use crate::combinator::{Parser, number, separated, take_while1};
+use crate::line_parser::{ParseError, parse_each_line};
 use indoc::indoc;
+use std::env;
+use std::fmt;
 use std::time::SystemTime;
 
 pub fn run() {
     println!("--- Squares With Three Sides --- ");
 
+    // The sample is opt in, so that it can be checked against the puzzle description
+    let input = if env::var(SAMPLE_VAR).is_ok() {
+        Input::Sample
+    } else {
+        Input::Real
+    };
+    println!("Using the {} input", input);
+
     let now = SystemTime::now();
-    let answer_a = part_a();
+    let answer_a = part_a(input);
     let duration = now.elapsed().expect("Elapsed failed");
     println!(
         "How many of the listed triangles are possible?\n {}\n in {}ms",
@@ -52,7 +65,7 @@ pub fn run() {
     );
 
     let now = SystemTime::now();
-    let answer_b = part_b();
+    let answer_b = part_b(input);
     let duration = now.elapsed().expect("Elapsed failed");
     println!(
         "In your puzzle input, and instead reading by columns, how many of the listed triangles are possible?\n {}\n in {}ms",
@@ -61,87 +74,69 @@ pub fn run() {
     );
 }
 
-pub fn part_a() -> i32 {
-    let mut count = 0;
-    for line in _INPUT_SAMPLE.lines() {
-        let sides: [i32; 3] = line
-            .split_whitespace()
-            .map(|s| s.parse::<i32>().unwrap())
-            .collect::<Vec<i32>>()
-            .try_into()
-            .unwrap();
+pub fn part_a(input: Input) -> usize {
+    rows(input.text())
+        .map(|row| row.expect("Invalid input"))
+        .filter(|sides| valid_triangle(sides))
+        .count()
+}
 
-        if valid_triangle(&sides) {
-            count += 1;
-        }
-    }
-    count
+pub fn part_b(input: Input) -> usize {
+    let rows = rows(input.text()).map(|row| row.expect("Invalid input"));
+    column_groups(rows, 3)
+        .filter(|sides| valid_triangle(sides))
+        .count()
 }
 
-pub fn part_b() -> i32 {
-    let stuff = _INPUT_SAMPLE
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|s| s.parse::<i32>().unwrap())
-                .collect::<Vec<i32>>()
-                .try_into()
-                .unwrap()
-        })
-        .fold(FoldZero::new(), |mut acc, p| {
-            acc.load(p);
-            acc
-        });
-    stuff.valid_count
+/// Reads the side lengths on each line of the document as they are needed
+fn rows(input: &str) -> impl Iterator<Item = Result<Vec<u32>, ParseError>> {
+    let spaces = take_while1("spaces", |c| c == ' ' || c == '\t');
+    let row = separated(number::<u32>(), spaces);
+    parse_each_line(input, move |line| row.parse(line))
 }
 
-struct FoldZero {
-    counter: usize,
-    a: [i32; 3],
-    b: [i32; 3],
-    c: [i32; 3],
-    valid_count: i32,
+fn valid_triangle(sides: &[u32]) -> bool {
+    sides.len() == 3 && valid_polygon(sides)
 }
 
-impl FoldZero {
-    fn new() -> Self {
-        FoldZero {
-            counter: 0,
-            a: [0; 3],
-            b: [0; 3],
-            c: [0; 3],
-            valid_count: 0,
+/// Which design document to read the triangles from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+    /// The specification given in the puzzle description
+    Sample,
+    /// The puzzle input
+    Real,
+}
+
+impl Input {
+    fn text(self) -> &'static str {
+        match self {
+            Input::Sample => INPUT_SAMPLE,
+            Input::Real => INPUT,
         }
     }
+}
 
-    fn load(&mut self, p: [i32; 3]) {
-        self.a[self.counter] = p[0];
-        self.b[self.counter] = p[1];
-        self.c[self.counter] = p[2];
-        self.counter += 1;
-
-        if self.counter == 3 {
-            if valid_triangle(&self.a) {
-                self.valid_count += 1;
-            }
-            if valid_triangle(&self.b) {
-                self.valid_count += 1;
-            }
-            if valid_triangle(&self.c) {
-                self.valid_count += 1;
-            }
-            self.counter = 0;
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Input::Sample => write!(f, "sample"),
+            Input::Real => write!(f, "real"),
         }
     }
 }
 
-fn valid_triangle(sides: &[i32; 3]) -> bool {
-    let mut sorted_sides = *sides;
-    sorted_sides.sort_unstable();
-    sorted_sides[0] + sorted_sides[1] > sorted_sides[2]
-}
+const SAMPLE_VAR: &str = "AOC_SAMPLE";
+
+const INPUT_SAMPLE: &str = indoc! {r#"
+101 301 501
+102 302 502
+103 303 503
+201 401 601
+202 402 602
+203 403 603"#};
 
-const _INPUT_SAMPLE: &str = indoc! {r#"
+const INPUT: &str = indoc! {r#"
   775  785  361
   622  375  125
   297  839  375
@@ -2060,17 +2055,41 @@ const _INPUT_SAMPLE: &str = indoc! {r#"
 
 #[cfg(test)]
 pub mod test {
+    use super::Input;
 
     #[test]
     pub fn test_part_a() {
-        let result = super::part_a();
+        let result = super::part_a(Input::Real);
         assert_eq!(result, 1032);
     }
 
     #[test]
     pub fn test_part_b() {
-        let result = super::part_b();
+        let result = super::part_b(Input::Real);
         assert_eq!(result, 1838);
     }
 
+    #[test]
+    pub fn test_sample() {
+        assert!(!super::valid_triangle(&[5, 10, 25]));
+
+        // Read by rows only the last three are triangles, but by columns they all are
+        assert_eq!(super::part_a(Input::Sample), 3);
+        assert_eq!(super::part_b(Input::Sample), 6);
+    }
+
+    #[test]
+    pub fn test_rows() {
+        let rows = |input| super::rows(input).collect::<Result<Vec<_>, _>>();
+        assert_eq!(
+            rows("  3  4 5\n\n 10 20\n"),
+            Ok(vec![vec![3, 4, 5], vec![10, 20]])
+        );
+        assert_eq!(
+            rows("3 4 5\n3 four 5").unwrap_err().to_string(),
+            "Line 2: Expected a number at 2, found \"four 5\""
+        );
+        assert!(rows("3 4 99999999999").is_err());
+        assert!(rows("3 -4 5").is_err());
+    }
 }
@@ -42,14 +42,24 @@
 //! What is the sector ID of the room where North Pole objects are stored?
 //!
 //! Your puzzle answer was 482. 
+use crate::cipher::{Cipher, LOWERCASE};
+use crate::combinator::{Parser, literal, number, separated, word};
+use crate::frequency::{Frequencies, TieBreak};
 use indoc::indoc;
+use regex::Regex;
+use std::sync::LazyLock;
 use std::time::SystemTime;
 
 pub fn run() {
     println!("--- Security Through Obscurity --- ");
 
     let now = SystemTime::now();
-    let answer_a = part_a();
+    let rooms = Room::all(_INPUT);
+    let duration = now.elapsed().expect("Elapsed failed");
+    println!("Parsed the kiosk list\n in {}ms", duration.as_millis());
+
+    let now = SystemTime::now();
+    let answer_a = part_a(&rooms);
     let duration = now.elapsed().expect("Elapsed failed");
     println!(
         "What is the sum of the sector IDs of the real rooms?\n {}\n in {}ms",
@@ -58,7 +68,7 @@ pub fn run() {
     );
 
     let now = SystemTime::now();
-    let answer_b = part_b();
+    let answer_b = part_b(&rooms);
     let duration = now.elapsed().expect("Elapsed failed");
     println!(
         "What is the sector ID of the room where North Pole objects are stored?\n {}\n in {}ms",
@@ -67,128 +77,116 @@ pub fn run() {
     );
 }
 
-pub fn part_a() -> u32 {
-    let mut total =  0u32;
+pub fn part_a(rooms: &[Room]) -> u32 {
+    rooms
+        .iter()
+        .filter(|room| room.is_real())
+        .map(|room| room.sector)
+        .sum()
+}
 
-    for line in _INPUT.lines() {
-        let mut state = 0;
-        let mut name = String::new();
-        let mut sector_string = String::new();
-        let mut checksum_string = String::new();
+pub fn part_b(rooms: &[Room]) -> u32 {
+    let query = Query::parse("northpole object storage").expect("Invalid query");
+    let (room, _) = search(rooms, &query).next().expect("No valid sector found");
+    room.sector
+}
 
-        for c in line.chars() {
-            match c {
-                '-'|'['|']' => {},
-                x if state == 0 && x.is_ascii_lowercase() => {
-                    name.push(x);
-                },
-                x if state == 0 => {
-                    state = 1;
-                    sector_string.push(x);
-                },
-                x if state == 1 && x.is_numeric() => {
-                    sector_string.push(x);
-                },
-                x => checksum_string.push(x),
-            }
-        };
+/// An entry on the kiosk, such as `aaaaa-bbb-z-y-x-123[abxyz]`
+#[derive(Debug, PartialEq)]
+pub struct Room {
+    /// Encrypted name, with the dashes between its words
+    name: String,
+    sector: u32,
+    checksum: String,
+}
 
-        if checksum(name.as_str(), 5) == checksum_string {
-            let sector = sector_string.parse::<u32>().unwrap();
-            total += sector;
-        } 
+impl Room {
+    fn parser() -> impl Parser<Room> {
+        separated(word(), literal("-"))
+            .skip(literal("-"))
+            .then(number())
+            .skip(literal("["))
+            .then(word())
+            .skip(literal("]"))
+            .map(|((words, sector), checksum)| Room {
+                name: words.join("-"),
+                sector,
+                checksum,
+            })
     }
-    total
-}
 
-pub fn part_b() -> u32 {
-    for line in _INPUT.lines() {
-        let mut state = 0;
-        let mut name = String::new();
-        let mut sector_string = String::new();
-        let mut checksum_string = String::new();
+    fn all(input: &str) -> Vec<Room> {
+        Room::parser().parse_lines(input).expect("Invalid input")
+    }
 
-        for c in line.chars() {
-            match c {
-                '-'|'['|']' => {},
-                x if state == 0 && x.is_ascii_lowercase() => {
-                    name.push(x);
-                },
-                x if state == 0 => {
-                    state = 1;
-                    sector_string.push(x);
-                },
-                x if state == 1 && x.is_numeric() => {
-                    sector_string.push(x);
-                },
-                x => checksum_string.push(x),
-            }
-        };
+    /// Decoy rooms have a checksum that doesn't match their name
+    fn is_real(&self) -> bool {
+        checksum(&self.name, 5) == self.checksum
+    }
 
-        if checksum(name.as_str(), 5) == checksum_string {
-            let sector = sector_string.parse::<u32>().unwrap();
-            let real_name = shift(name.as_str(), sector);
-            if real_name.contains("northpoleobjectstorage") {
-                // println!("Decrypted name: {} with sector {}", real_name, sector);
-                return sector;
-            }
-        } 
-    } 
-    panic!("No valid sector found");
+    /// Shifts the name forward by the sector, with the dashes becoming spaces. The rooms were
+    /// encrypted by shifting back, so that is the key.
+    fn decrypt(&self) -> String {
+        NAME_CIPHER
+            .decrypt(&self.name, -i64::from(self.sector))
+            .expect("Room names are only letters and dashes")
+    }
 }
 
-fn checksum(name: &str, size: usize) -> String {
-    let counts = letter_counts(name);
-    let mut indexed = counts.iter().enumerate().collect::<Vec<(usize, &u32)>>();
-
-    // Sort the indexed counts by counts descending, then by index ascending
-    indexed.sort_unstable_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(&b.0)));
-
-    // Take the first X number of indexes and convert them back to characters, and then collect to
-    // a string
-    indexed
-        .iter()
-        .take(size)
-        .map(|x| char::from_u32(LETTER_BASE + x.0 as u32).unwrap())
-        .collect::<String>()
+/// What to look for in a decrypted room name
+#[derive(Debug)]
+pub enum Query {
+    Contains(String),
+    Matches(Regex),
 }
 
-fn letter_counts(s: &str) -> [u32; 26] {
-    let mut counts = [0u32; 26];
+impl Query {
+    /// Reads a query written as text. A regex is wrapped in slashes, such as `/^north/`, and
+    /// anything else is looked for as it is.
+    fn parse(s: &str) -> Result<Query, String> {
+        match s.strip_prefix('/').and_then(|s| s.strip_suffix('/')) {
+            Some(pattern) => Regex::new(pattern)
+                .map(Query::Matches)
+                .map_err(|e| format!("Invalid regex {pattern:?}: {e}")),
+            None => Ok(Query::Contains(String::from(s))),
+        }
+    }
 
-    // Count all of the letters in the name
-    for c in s.chars() {
-        match c {
-            '-' => {}
-            x if x.is_ascii_lowercase() => {
-                let pos = (x as u32 - LETTER_BASE) as usize;
-                counts[pos] += 1;
-            }
-            _ => panic!("Invalid character in name"),
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Query::Contains(s) => name.contains(s.as_str()),
+            Query::Matches(regex) => regex.is_match(name),
         }
     }
-    counts
 }
 
-fn shift(s: &str, times: u32) -> String {
-    let mut result = String::new();
+/// Finds the real rooms whose decrypted name matches the query, in the order they are listed, along
+/// with their decrypted name. Decoy rooms are skipped, as decrypting them gives nonsense.
+fn search<'a>(rooms: &'a [Room], query: &'a Query) -> impl Iterator<Item = (&'a Room, String)> {
+    rooms
+        .iter()
+        .filter(|room| room.is_real())
+        .map(|room| (room, room.decrypt()))
+        .filter(|(_, name)| query.matches(name))
+}
 
-    for c in s.chars() {
-        match c {
-            'a'..='z' => {
-                let offset = (c as u32 - LETTER_BASE + times) % 26;
-                result.push(char::from_u32(LETTER_BASE + offset).unwrap());
-            }
-            '-' => {
-                result.push(' ');
-            }
-            _ => panic!("Invalid character in name"),
-        }
-    }
-    result
+/// The `size` most common letters in the name, with ties broken alphabetically. Letters that are
+/// not in the name are all tied on 0, so when it has fewer than `size` different letters the
+/// checksum goes on with them alphabetically.
+fn checksum(name: &str, size: usize) -> String {
+    let frequencies = Frequencies::new(name.chars().filter(|c| *c != '-'));
+    let unused = LOWERCASE.chars().filter(|c| frequencies.count(c) == 0);
+    frequencies
+        .top(size, TieBreak::Ascending)
+        .into_iter()
+        .map(|(c, _)| c)
+        .chain(unused)
+        .take(size)
+        .collect()
 }
 
-const LETTER_BASE: u32 = 'a' as u32;
+static NAME_CIPHER: LazyLock<Cipher> =
+    LazyLock::new(|| Cipher::new().alphabet(LOWERCASE).separator('-', ' '));
 
 const _INPUT_SAMPLE: &str = indoc! {r#"
 aaaaa-bbb-z-y-x-123[abxyz]
@@ -1174,15 +1172,17 @@ bxaxipgn-vgpst-qphzti-rdcipxcbtci-635[ipctx]"#};
 
 #[cfg(test)]
 pub mod test {
+    use crate::combinator::Parser;
+
     #[test]
     pub fn test_part_a() {
-        let result = super::part_a();
+        let result = super::part_a(&super::Room::all(super::_INPUT));
         assert_eq!(result, 361724);
     }
 
     #[test]
     pub fn test_part_b() {
-        let result = super::part_b();
+        let result = super::part_b(&super::Room::all(super::_INPUT));
         assert_eq!(result, 482);
     }
 
@@ -1203,4 +1203,54 @@ pub mod test {
         result = super::checksum("totally-real-room-", 5);
         assert_ne!(result, "decoy");
     }
+
+    #[test]
+    pub fn room() {
+        let rooms = super::Room::all(super::_INPUT_SAMPLE);
+        assert_eq!(
+            rooms[0],
+            super::Room {
+                name: String::from("aaaaa-bbb-z-y-x"),
+                sector: 123,
+                checksum: String::from("abxyz"),
+            }
+        );
+        let real: Vec<bool> = rooms.iter().map(|room| room.is_real()).collect();
+        assert_eq!(real, vec![true, true, true, false]);
+        assert_eq!(super::part_a(&rooms), 1514);
+
+        let room = super::Room::parser()
+            .parse("qzmt-zixmtkozy-ivhz-343[zimth]")
+            .unwrap();
+        assert_eq!(room.decrypt(), "very encrypted name");
+    }
+
+    #[test]
+    pub fn test_short_names() {
+        // Names with fewer than five different letters are padded with the unused ones
+        assert_eq!(super::checksum("aaa-b", 5), "abcde");
+        assert_eq!(super::checksum("z-y-zz", 5), "zyabc");
+        let room = super::Room::parser().parse("aaa-b-12[abcde]").unwrap();
+        assert!(room.is_real());
+    }
+
+    #[test]
+    pub fn search() {
+        let rooms = super::Room::all(super::_INPUT);
+        let query = super::Query::parse("/^northpole object/").unwrap();
+        assert!(matches!(query, super::Query::Matches(_)));
+        let found: Vec<(u32, String)> = super::search(&rooms, &query)
+            .map(|(room, name)| (room.sector, name))
+            .collect();
+        assert_eq!(found, vec![(482, String::from("northpole object storage"))]);
+
+        let query = super::Query::parse("candy").unwrap();
+        assert_eq!(super::search(&rooms, &query).count(), 124);
+        // A lone slash is looked for as it is
+        assert_eq!(
+            super::search(&rooms, &super::Query::parse("/").unwrap()).count(),
+            0
+        );
+        assert!(super::Query::parse("/(/").is_err());
+    }
 }
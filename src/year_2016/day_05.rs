@@ -56,8 +56,9 @@
 //! solution if it uses a cinematic "decrypting" animation.
 //!
 //! Your puzzle answer was 694190cd.
-use md5::{Digest, Md5};
-use std::{io::Write, time::SystemTime};
+use crate::hash_search::{cached_search, nibble, zeros};
+use std::io::{self, IsTerminal, Write};
+use std::time::SystemTime;
 
 pub fn run() {
     println!("--- How About a Nice Game of Chess? --- ");
@@ -71,90 +72,122 @@ pub fn run() {
         duration.as_millis()
     );
 
-    print!("Given the actual Door ID and this new method, what is the password?\n ");
     let now = SystemTime::now();
-    let _answer_b = part_b();
+    let answer_b = if io::stdout().is_terminal() {
+        // Only animate when someone is watching, as the backspaces garble logs and piped output
+        let mut animation = Animation::new();
+        let answer = part_b_with(|password| animation.show(password));
+        animation.clear();
+        answer
+    } else {
+        part_b()
+    };
     let duration = now.elapsed().expect("Elapsed failed");
-    println!("\n in {}ms", duration.as_millis());
+    println!(
+        "Given the actual Door ID and this new method, what is the password?\n {}\n in {}ms",
+        answer_b,
+        duration.as_millis()
+    );
 }
 
 pub fn part_a() -> String {
-    let mut length = 0;
-    let mut i = 0;
-    let mut password = String::new();
-
-    while length < PASSWORD_LENGTH {
-        let candidate = format!("{}{}", INPUT, i);
-        let hash = Md5::new_with_prefix(candidate.as_bytes()).finalize();
-
-        // Convert the hash into a hex string
-        let hex_hash = base16ct::lower::encode_string(&hash);
-
-        // If the hex hash starts with five zeroes, we have a candidate
-        if hex_hash.starts_with(CANDIDATE_PREFIX) {
-            let ch = hex_hash.chars().nth(5).unwrap();
-            password.push(ch);
-            length += 1;
-        }
-        i += 1;
-    }
-
-    password
+    // Every hit is a candidate, and the sixth hex digit is the next character
+    cached_search(CANDIDATES, INPUT, zeros(CANDIDATE_ZEROS), PASSWORD_LENGTH)
+        .map(|(_, digest)| hex_char(nibble(&digest, 5)))
+        .collect()
 }
 
 pub fn part_b() -> String {
-    let mut i = 0;
+    part_b_with(|_| {})
+}
 
-    // We are going to do the suggested animation of printing the password as we find characters
+/// Solves part B, calling `display` with the partially decrypted password when the search starts
+/// and each time a new character is found
+pub fn part_b_with<F>(mut display: F) -> String
+where
+    F: FnMut(&[char]),
+{
     // The password starts as all underscores
     let mut password = [MISSING_CHAR; PASSWORD_LENGTH];
-    password.iter().for_each(|c| print!("{}", c));
-
-    while password.contains(&'_') {
-        let candidate = format!("{}{}", INPUT, i);
-        let hash = Md5::new_with_prefix(candidate.as_bytes()).finalize();
-
-        // Convert the hash into a hex string
-        let hex_hash = base16ct::lower::encode_string(&hash);
-
-        // If the hex hash starts with five zeroes, we have a candidate
-        if hex_hash.starts_with(CANDIDATE_PREFIX) {
-            let mut chars = hex_hash.chars();
-
-            // Check to see if the the 6th character is a valid position, and if that position is
-            // not already filled
-            let pos = chars.nth(5).unwrap() as usize - DIGIT_BASE;
-            if (0..8).contains(&pos) && password[pos] == MISSING_CHAR {
-                // We found a new character for the password
-                let ch = chars.next().unwrap();
-                password[pos] = ch;
-
-                // Backspace over the previous password. Make sure to flush stdout so the backspaces
-                // are printed before we print the password
-                (0..PASSWORD_LENGTH).for_each(|_| print!("{}", BACKSPACE));
-                std::io::stdout().flush().unwrap();
-
-                // Print out the password with the newly found character
-                password.iter().for_each(|c| print!("{}", c));
-                std::io::stdout().flush().unwrap();
+    display(&password);
+
+    // There is no telling how many candidates it takes to fill every position, so keep taking
+    // them until the password is complete. The 6th character is the position and the 7th is the
+    // character to put there.
+    let candidates = cached_search(CANDIDATES, INPUT, zeros(CANDIDATE_ZEROS), usize::MAX)
+        .map(|(_, digest)| (nibble(&digest, 5) as usize, nibble(&digest, 6)));
+    for (pos, value) in candidates {
+        // Only the first candidate for each valid position counts
+        if pos < PASSWORD_LENGTH && password[pos] == MISSING_CHAR {
+            password[pos] = hex_char(value);
+            display(&password);
+            if !password.contains(&MISSING_CHAR) {
+                break;
             }
         }
-        i += 1;
     }
-    // Remove the password from the console
-    // (0..PASSWORD_LENGTH).for_each(|_| print!("{} {}", BACKSPACE, BACKSPACE));
-    // println!();
     password.iter().collect::<String>()
 }
 
+/// The suggested "decrypting" animation, which redraws the password in place on the console as
+/// characters are found
+struct Animation {
+    shown: usize,
+}
+
+impl Animation {
+    fn new() -> Animation {
+        Animation { shown: 0 }
+    }
+
+    fn show(&mut self, password: &[char]) {
+        // Backspace over the previous password before printing the password with the newly found
+        // character. Make sure to flush stdout so it is drawn right away.
+        let mut stdout = io::stdout();
+        (0..self.shown).for_each(|_| print!("{}", BACKSPACE));
+        password.iter().for_each(|c| print!("{}", c));
+        stdout.flush().unwrap();
+        self.shown = password.len();
+    }
+
+    fn clear(&mut self) {
+        // Remove the password from the console, so the answer is printed the same way whether it
+        // was animated or not
+        let mut stdout = io::stdout();
+        (0..self.shown).for_each(|_| print!("{} {}", BACKSPACE, BACKSPACE));
+        stdout.flush().unwrap();
+        self.shown = 0;
+    }
+}
+
+fn hex_char(n: u8) -> char {
+    char::from_digit(u32::from(n), 16).unwrap()
+}
+
 const PASSWORD_LENGTH: usize = 8;
 
-const DIGIT_BASE: usize = '0' as usize;
+const CANDIDATE_ZEROS: usize = 5;
 
-const CANDIDATE_PREFIX: &str = "00000";
+/// Describes the candidate hashes for the search cache, which both parts share
+const CANDIDATES: &str = "5 zeros";
 
 const MISSING_CHAR: char = '_';
 
 const BACKSPACE: &str = "\x08";
 
 const INPUT: &str = "uqwqemis";
+
+#[cfg(test)]
+pub mod test {
+    #[test]
+    pub fn test_part_a() {
+        let result = super::part_a();
+        assert_eq!(result, "1a3099aa");
+    }
+
+    #[test]
+    pub fn test_part_b() {
+        let result = super::part_b();
+        assert_eq!(result, "694190cd");
+    }
+}
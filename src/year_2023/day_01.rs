@@ -91,11 +91,11 @@ fn part_a() -> u32 {
     let mut sum = 0u32;
 
     for line in INPUT_A.lines() {
-        if let Some(a) = line.chars().find(|c| c.is_ascii_digit()) {
-            if let Some(b) = line.chars().rev().find(|c| c.is_ascii_digit()) {
-                sum += a.to_digit(10).unwrap() * 10;
-                sum += b.to_digit(10).unwrap();
-            }
+        if let Some(a) = line.chars().find(|c| c.is_ascii_digit())
+            && let Some(b) = line.chars().rev().find(|c| c.is_ascii_digit())
+        {
+            sum += a.to_digit(10).unwrap() * 10;
+            sum += b.to_digit(10).unwrap();
         }
     }
     sum
@@ -60,17 +60,17 @@
 //!
 //! Following these rotations would cause the dial to move as follows:
 //!
-//!     The dial starts by pointing at 50.
-//!     The dial is rotated L68 to point at 82.
-//!     The dial is rotated L30 to point at 52.
-//!     The dial is rotated R48 to point at 0.
-//!     The dial is rotated L5 to point at 95.
-//!     The dial is rotated R60 to point at 55.
-//!     The dial is rotated L55 to point at 0.
-//!     The dial is rotated L1 to point at 99.
-//!     The dial is rotated L99 to point at 0.
-//!     The dial is rotated R14 to point at 14.
-//!     The dial is rotated L82 to point at 32.
+//! - The dial starts by pointing at 50.
+//! - The dial is rotated L68 to point at 82.
+//! - The dial is rotated L30 to point at 52.
+//! - The dial is rotated R48 to point at 0.
+//! - The dial is rotated L5 to point at 95.
+//! - The dial is rotated R60 to point at 55.
+//! - The dial is rotated L55 to point at 0.
+//! - The dial is rotated L1 to point at 99.
+//! - The dial is rotated L99 to point at 0.
+//! - The dial is rotated R14 to point at 14.
+//! - The dial is rotated L82 to point at 32.
 //!
 //! Because the dial points at 0 a total of three times during this process, the password in this
 //! example is 3.
@@ -97,17 +97,17 @@
 //! Following the same rotations as in the above example, the dial points at zero a few extra times
 //! during its rotations:
 //!
-//!     The dial starts by pointing at 50.
-//!     The dial is rotated L68 to point at 82; during this rotation, it points at 0 once.
-//!     The dial is rotated L30 to point at 52.
-//!     The dial is rotated R48 to point at 0.
-//!     The dial is rotated L5 to point at 95.
-//!     The dial is rotated R60 to point at 55; during this rotation, it points at 0 once.
-//!     The dial is rotated L55 to point at 0.
-//!     The dial is rotated L1 to point at 99.
-//!     The dial is rotated L99 to point at 0.
-//!     The dial is rotated R14 to point at 14.
-//!     The dial is rotated L82 to point at 32; during this rotation, it points at 0 once.
+//! - The dial starts by pointing at 50.
+//! - The dial is rotated L68 to point at 82; during this rotation, it points at 0 once.
+//! - The dial is rotated L30 to point at 52.
+//! - The dial is rotated R48 to point at 0.
+//! - The dial is rotated L5 to point at 95.
+//! - The dial is rotated R60 to point at 55; during this rotation, it points at 0 once.
+//! - The dial is rotated L55 to point at 0.
+//! - The dial is rotated L1 to point at 99.
+//! - The dial is rotated L99 to point at 0.
+//! - The dial is rotated R14 to point at 14.
+//! - The dial is rotated L82 to point at 32; during this rotation, it points at 0 once.
 //!
 //! In this example, the dial points at 0 three times at the end of a rotation, plus three more
 //! times during a rotation. So, in this example, the new password would be 6.
@@ -117,8 +117,9 @@
 //!
 //! Using password method 0x434C49434B, what is the password to open the door?
 //!
-//! Your puzzle answer was 6860. 
+//! Your puzzle answer was 6860.
 //!
+use crate::combinator::{Parser, literal, number};
 use indoc::indoc;
 use std::time::SystemTime;
 
@@ -147,15 +148,8 @@ pub fn run() {
 fn part_a() -> i32 {
     let mut solution = 0;
     let mut position = 50;
-    for line in INPUT.lines() {
-        let trimmed = line.trim();
-        let ticks = match &trimmed[0..1] {
-            "L" => -trimmed[1..].parse::<i32>().unwrap(),
-            "R" => line[1..].parse::<i32>().unwrap(),
-            _ => panic!("Unexpected direction"),
-        };
-
-        position = (position + ticks) % 100;
+    for (direction, ticks) in rotations(INPUT) {
+        position = (position + direction * ticks) % 100;
         if position == 0 {
             solution += 1;
         }
@@ -166,16 +160,7 @@ fn part_a() -> i32 {
 fn part_b() -> i32 {
     let mut solution = 0;
     let mut position = 50;
-    for line in INPUT.lines() {
-        let trimmed = line.trim();
-        let x = match &trimmed[0..1] {
-            "L" => -1,
-            "R" => 1,
-            _ => panic!("Unexpected direction"),
-        };
-
-        let ticks = trimmed[1..].parse::<i32>().unwrap();
-
+    for (x, ticks) in rotations(INPUT) {
         // Brute force rotations
         for _ in 0..ticks {
             position += x;
@@ -193,6 +178,15 @@ fn part_b() -> i32 {
     solution
 }
 
+/// Reads each rotation as its direction, -1 for left and 1 for right, and the number of ticks
+fn rotations(input: &str) -> Vec<(i32, i32)> {
+    let direction = literal("L").map(|_| -1).or(literal("R").map(|_| 1));
+    direction
+        .then(number())
+        .parse_lines(input)
+        .expect("Invalid input")
+}
+
 const _INPUT_SAMPLE: &str = indoc! {r#"
 L68
 L30
@@ -4880,4 +4874,15 @@ pub mod test {
         let result = super::part_b();
         assert_eq!(result, 6860);
     }
+
+    #[test]
+    fn test_line_endings() {
+        // Trailing whitespace and Windows line endings read the same as the plain sample
+        let padded = super::_INPUT_SAMPLE.replace('\n', " \r\n");
+        assert_eq!(
+            super::rotations(&padded),
+            super::rotations(super::_INPUT_SAMPLE)
+        );
+        assert_eq!(super::rotations(&padded).len(), 10);
+    }
 }